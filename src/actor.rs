@@ -11,6 +11,7 @@ use miniserde::json;
 
 pub struct Actor {
     pub identifier: ActorType,
+    pub iid: Option<String>,
     pub grid_x: i32,
     pub grid_y: i32,
     pub offset_x: i32,
@@ -60,6 +61,7 @@ impl Actor {
     ) -> Self {
         Self {
             identifier,
+            iid: None,
             grid_x,
             grid_y,
             offset_x: 0,
@@ -98,6 +100,7 @@ impl Actor {
             entity_json.grid[1] as i32,
             rel_path,
         );
+        actor.iid = Some(entity_json.iid.clone());
 
         if actor.identifier == ActorType::Chest {
             let chest_type_field = entity_json
@@ -133,6 +136,10 @@ impl Actor {
         self.sprite.start_animation(tag);
     }
 
+    pub fn start_face_animation(&mut self, dir: Direction) {
+        self.sprite.start_face_animation(dir);
+    }
+
     pub fn start_walk_animation(&mut self, dir: Direction) {
         self.sprite.start_walk_animation(dir);
     }
@@ -0,0 +1,45 @@
+// Scenery drawn behind the enemies in battle, chosen by the level the battle happens in, or by the
+// boss being fought.
+#[derive(Clone, Copy)]
+pub enum Backdrop {
+    Grassland,
+    Stone,
+    Flooded,
+    Lava,
+    EarthLair,
+    WaterLair,
+    FireLair,
+}
+
+impl Backdrop {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "Grassland" => Some(Self::Grassland),
+            "Stone" => Some(Self::Stone),
+            "Flooded" => Some(Self::Flooded),
+            "Lava" => Some(Self::Lava),
+            "EarthLair" => Some(Self::EarthLair),
+            "WaterLair" => Some(Self::WaterLair),
+            "FireLair" => Some(Self::FireLair),
+            _ => None,
+        }
+    }
+
+    pub fn texture_path(&self) -> &'static str {
+        match self {
+            Self::Grassland => "backdrop-grassland.png",
+            Self::Stone => "backdrop-stone.png",
+            Self::Flooded => "backdrop-flooded.png",
+            Self::Lava => "backdrop-lava.png",
+            Self::EarthLair => "backdrop-earth-lair.png",
+            Self::WaterLair => "backdrop-water-lair.png",
+            Self::FireLair => "backdrop-fire-lair.png",
+        }
+    }
+}
+
+impl From<&str> for Backdrop {
+    fn from(s: &str) -> Self {
+        Self::from_name(s).unwrap_or_else(|| panic!("unknown backdrop: {s}"))
+    }
+}
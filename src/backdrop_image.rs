@@ -0,0 +1,73 @@
+use crate::backdrop::*;
+use crate::resources::*;
+use crate::shaders::quad_shader;
+use crate::{get_gctx, SCREEN_HEIGHT, SCREEN_WIDTH};
+
+use miniquad::{
+    Bindings, BufferSource, BufferType, BufferUsage, GlContext, Pipeline, RenderingBackend,
+    TextureWrap, UniformsSource,
+};
+
+// A backdrop filling an area, repeated across it horizontally and cut off at the bottom.
+pub struct BackdropImage {
+    offset: [f32; 2],
+    px_texture_size: [f32; 2],
+    bindings: Bindings,
+    quad_pipeline: Pipeline,
+}
+
+impl BackdropImage {
+    pub fn new(
+        gctx: &mut GlContext,
+        res: &Resources,
+        backdrop: Backdrop,
+        x: i32,
+        y: i32,
+        width: i32,
+        height: i32,
+    ) -> Self {
+        let texture = res.textures_by_path[backdrop.texture_path()];
+        gctx.texture_set_wrap(texture.tex_id, TextureWrap::Repeat, TextureWrap::Clamp);
+
+        let height = height.min(texture.height as i32);
+        let inst_data: [[[f32; 2]; 3]; 1] =
+            [[[width as f32, height as f32], [0.0, 0.0], [0.0, 0.0]]];
+        let inst_buf = gctx.new_buffer(
+            BufferType::VertexBuffer,
+            BufferUsage::Immutable,
+            BufferSource::slice(&inst_data[..]),
+        );
+
+        Self {
+            offset: [x as f32, y as f32],
+            px_texture_size: [texture.width as f32, texture.height as f32],
+            bindings: Bindings {
+                vertex_buffers: vec![res.quad_vbuf, inst_buf],
+                index_buffer: res.quad_ibuf,
+                images: vec![texture.tex_id],
+            },
+            quad_pipeline: res.quad_pipeline,
+        }
+    }
+
+    pub fn draw(&self, gctx: &mut GlContext) {
+        gctx.apply_pipeline(&self.quad_pipeline);
+        gctx.apply_bindings(&self.bindings);
+        gctx.apply_uniforms(UniformsSource::table(&quad_shader::Uniforms {
+            px_src_offset: [0.0, 0.0],
+            px_dest_offset: self.offset,
+            px_framebuffer_size: [SCREEN_WIDTH as f32, SCREEN_HEIGHT as f32],
+            px_texture_size: self.px_texture_size,
+            tint: [0.0; 4],
+        }));
+        gctx.draw(0, 6, 1);
+    }
+}
+
+impl Drop for BackdropImage {
+    fn drop(&mut self) {
+        let gctx = get_gctx();
+
+        gctx.delete_buffer(self.bindings.vertex_buffers[1]);
+    }
+}
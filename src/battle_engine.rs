@@ -0,0 +1,665 @@
+// Turn resolution for battles, kept free of any presentation so that it can be shared by the
+// `Battle` mode and the battle simulator.
+
+use crate::enemy::*;
+use crate::progress::*;
+use crate::random::*;
+
+// Damage that Coric takes from enemies while defending is multiplied by this.
+const DEFEND_DAMAGE_FACTOR: f32 = 0.5;
+
+const CRIT_DAMAGE_FACTOR: f32 = 1.5;
+
+// Chance out of 100 for Coric's edge magic to inflict its status on the enemy.
+const EDGE_STATUS_CHANCE: u32 = 25;
+const EDGE_STATUS_TURNS: u32 = 3;
+
+// Chance out of 100 for each opening of a random encounter when Coric and the enemies are evenly
+// matched, and the most that either can reach.
+const BASE_OPENING_CHANCE: i32 = 6;
+const MAX_OPENING_CHANCE: i32 = 30;
+
+pub struct BattleEngine {
+    pub enemies: Vec<BattleEnemy>,
+    pub follow_up: Option<(Magic, usize)>,
+    pub player_statuses: StatusEffects,
+    defending: bool,
+    fleeing: bool,
+}
+
+// One of the enemies being fought, along with everything about it that only lasts the battle.
+pub struct BattleEnemy {
+    pub enemy: Enemy,
+    // The enemy's name with a letter added if there's more than one of its kind, e.g. "Rat B".
+    pub name: String,
+    pub max_hp: i32,
+    pub statuses: StatusEffects,
+    // What attacking this enemy would do this turn, rolled in `start_turn`.
+    attack_damage: i32,
+    // 0 while the enemy is still using its base actions, otherwise 1 + index into its phases.
+    phase: usize,
+    turns: u32,
+}
+
+pub struct EnemyOutcome {
+    pub phase_changed: bool,
+    pub msg: String,
+    pub hit: Hit,
+    pub damage: Option<i32>,
+    pub inflicted: Option<Status>,
+}
+
+#[derive(Clone, Copy, Eq, PartialEq)]
+pub enum Hit {
+    Normal,
+    Critical,
+    Miss,
+}
+
+#[derive(Clone, Copy, Eq, PartialEq)]
+pub enum Opening {
+    Normal,
+    // Coric gets a free turn before the enemies can act.
+    Preemptive,
+    // The enemies get a free turn before Coric can act.
+    Ambush,
+}
+
+pub enum PlayerAction {
+    // Target enemy.
+    Fight(usize),
+    // Magic choice and target enemy; the target is ignored for Heal.
+    Magic(usize, usize),
+    // Item choice and target enemy; the target is only used for Lenses.
+    Item(usize, usize),
+    Defend,
+    Run,
+}
+
+pub enum PlayerOutcome {
+    Attack {
+        target: usize,
+        hit: Hit,
+        damage: i32,
+    },
+    Heal {
+        hp: i32,
+    },
+    Spell {
+        target: usize,
+        magic: Magic,
+        // HP recovered instead when the enemy absorbs the magic.
+        damage: i32,
+        affinity: Affinity,
+        inflicted: Option<Status>,
+    },
+    Item {
+        item: Item,
+        hp: i32,
+        mp: i32,
+        cured: bool,
+    },
+    Defend {
+        mp: i32,
+    },
+    Scan {
+        target: usize,
+    },
+    RunFailed,
+    RanAway,
+}
+
+// Statuses only last for the battle they were inflicted in, so they're kept here instead of in
+// `Progress`, and vanish along with the rest of the battle.
+#[derive(Default)]
+pub struct StatusEffects {
+    turns_left: [u32; Status::ALL.len()],
+}
+
+pub enum StatusEvent {
+    Poison { target: Target, damage: i32 },
+    WoreOff { target: Target, status: Status },
+}
+
+#[derive(Clone, Copy)]
+pub enum Target {
+    Coric,
+    Enemy(usize),
+}
+
+impl BattleEngine {
+    pub fn new(enemies: Vec<Enemy>) -> Self {
+        assert!(!enemies.is_empty());
+
+        let names: Vec<String> = enemies
+            .iter()
+            .enumerate()
+            .map(|(i, enemy)| {
+                let same_kind = enemies.iter().filter(|e| e.name == enemy.name);
+                if same_kind.count() > 1 {
+                    let nth = enemies[..i].iter().filter(|e| e.name == enemy.name).count();
+                    format!("{} {}", enemy.name, char::from(b'A' + nth as u8))
+                } else {
+                    enemy.name.clone()
+                }
+            })
+            .collect();
+
+        Self {
+            enemies: enemies
+                .into_iter()
+                .zip(names)
+                .map(|(enemy, name)| BattleEnemy {
+                    name,
+                    max_hp: enemy.hp,
+                    enemy,
+                    statuses: StatusEffects::default(),
+                    attack_damage: 0,
+                    phase: 0,
+                    turns: 0,
+                })
+                .collect(),
+            follow_up: None,
+            player_statuses: StatusEffects::default(),
+            defending: false,
+            fleeing: false,
+        }
+    }
+
+    pub fn all_enemies_defeated(&self) -> bool {
+        self.enemies.iter().all(BattleEnemy::defeated)
+    }
+
+    fn damage_enemy(&mut self, progress: &mut Progress, target: usize, damage: i32) {
+        let enemy = &mut self.enemies[target];
+        let hp_lost = damage.min(enemy.enemy.hp);
+        enemy.enemy.hp -= hp_lost;
+        progress.stats.damage_dealt += hp_lost as u32;
+        if damage > 0 {
+            enemy.statuses.cure(Status::Sleep);
+        }
+    }
+
+    // The caller is responsible for only letting enemies that aren't defeated take turns.
+    pub fn enemy_turn(&mut self, rng: &mut Rng, progress: &mut Progress, i: usize) -> EnemyOutcome {
+        let follow_up = self.follow_up.is_some();
+        let enemy = &mut self.enemies[i];
+        enemy.turns += 1;
+
+        // Skip straight to the latest phase whose threshold has been crossed.
+        let hp_percent = enemy.enemy.hp * 100 / enemy.max_hp;
+        let old_phase = enemy.phase;
+        while enemy
+            .enemy
+            .phases
+            .get(enemy.phase)
+            .is_some_and(|phase| hp_percent < phase.hp_below)
+        {
+            enemy.phase += 1;
+        }
+        let phase_changed = enemy.phase != old_phase;
+
+        if enemy.statuses.has(Status::Sleep) {
+            return EnemyOutcome {
+                phase_changed,
+                msg: String::from("is fast asleep…"),
+                hit: Hit::Normal,
+                damage: None,
+                inflicted: None,
+            };
+        }
+
+        let mut enemy_action = EnemyAction {
+            chance: 100,
+            msg: String::from("attacks!"),
+            damage_factor: Some(1.0),
+            inflict: None,
+            when: None,
+        };
+
+        let actions = match enemy.current_phase() {
+            Some(phase) => &phase.actions,
+            None => &enemy.enemy.actions,
+        };
+        let mut enemy_roll = rng.random(100);
+        for action in actions.iter().filter(|a| {
+            a.when
+                .as_ref()
+                .map_or(true, |w| w.holds(hp_percent, enemy.turns, follow_up))
+        }) {
+            if enemy_roll < action.chance {
+                enemy_action = action.clone();
+                break;
+            } else {
+                enemy_roll -= action.chance;
+            }
+        }
+
+        let weaken_factor = if enemy.statuses.has(Status::Weaken) {
+            0.5
+        } else {
+            1.0
+        };
+        let defend_factor = if self.defending {
+            DEFEND_DAMAGE_FACTOR
+        } else {
+            1.0
+        };
+        let hit = if enemy_action.damage_factor.is_some() {
+            roll_hit(
+                rng,
+                enemy.enemy.accuracy,
+                progress.evasion(),
+                enemy.enemy.crit_chance,
+            )
+        } else {
+            Hit::Normal
+        };
+        let crit_factor = if hit == Hit::Critical {
+            CRIT_DAMAGE_FACTOR
+        } else {
+            1.0
+        };
+        let damage = enemy_action
+            .damage_factor
+            .filter(|_| hit != Hit::Miss)
+            .map(|damage_factor| {
+                let base_damage = calc_base_damage(enemy.enemy.attack, progress.defense)
+                    * damage_factor
+                    * weaken_factor
+                    * defend_factor
+                    * crit_factor;
+                let damage = base_damage.trunc() as i32
+                    + if (rng.random(100) as f32) < base_damage.fract() * 100.0 {
+                        1
+                    } else {
+                        0
+                    };
+
+                let hp_lost = damage.min(progress.hp);
+                progress.hp -= hp_lost;
+                progress.stats.damage_taken += hp_lost as u32;
+                if damage > 0 {
+                    self.player_statuses.cure(Status::Sleep);
+                }
+
+                damage
+            });
+
+        // Dodging an attack also dodges anything it would have inflicted.
+        let inflicted = enemy_action
+            .inflict
+            .filter(|_| hit != Hit::Miss)
+            .map(|inflict| {
+                self.player_statuses.inflict(inflict.status, inflict.turns);
+                inflict.status
+            });
+
+        EnemyOutcome {
+            phase_changed,
+            msg: enemy_action.msg,
+            hit,
+            damage,
+            inflicted,
+        }
+    }
+
+    // Call after both Coric and the enemies have had their turns.  Poison deals damage, then all
+    // statuses count down a turn.
+    pub fn end_turn(&mut self, progress: &mut Progress) -> Vec<StatusEvent> {
+        let mut events = Vec::new();
+
+        if self.player_statuses.has(Status::Poison) {
+            let damage = (progress.max_hp / 16).max(1);
+            let hp_lost = damage.min(progress.hp);
+            progress.hp -= hp_lost;
+            progress.stats.damage_taken += hp_lost as u32;
+            events.push(StatusEvent::Poison {
+                target: Target::Coric,
+                damage,
+            });
+        }
+        for (i, enemy) in self.enemies.iter_mut().enumerate() {
+            if !enemy.defeated() && enemy.statuses.has(Status::Poison) {
+                let damage = (enemy.max_hp / 16).max(1);
+                let hp_lost = damage.min(enemy.enemy.hp);
+                enemy.enemy.hp -= hp_lost;
+                progress.stats.damage_dealt += hp_lost as u32;
+                events.push(StatusEvent::Poison {
+                    target: Target::Enemy(i),
+                    damage,
+                });
+            }
+        }
+
+        for status in self.player_statuses.count_down() {
+            events.push(StatusEvent::WoreOff {
+                target: Target::Coric,
+                status,
+            });
+        }
+        for (i, enemy) in self.enemies.iter_mut().enumerate() {
+            if enemy.defeated() {
+                enemy.statuses = StatusEffects::default();
+            }
+            for status in enemy.statuses.count_down() {
+                events.push(StatusEvent::WoreOff {
+                    target: Target::Enemy(i),
+                    status,
+                });
+            }
+        }
+
+        events
+    }
+
+    // True if the player failed to run, but will get away after the enemies' turns.
+    pub fn fleeing(&self) -> bool {
+        self.fleeing
+    }
+
+    // True if Coric is asleep and must skip his turn, i.e. not call `player_turn`.
+    pub fn player_asleep(&self) -> bool {
+        self.player_statuses.has(Status::Sleep)
+    }
+
+    // The caller is responsible for only choosing magic that's known and affordable while Coric
+    // isn't silenced, items that are in stock, and targets that aren't defeated.
+    pub fn player_turn(
+        &mut self,
+        rng: &mut Rng,
+        progress: &mut Progress,
+        action: PlayerAction,
+    ) -> PlayerOutcome {
+        match action {
+            PlayerAction::Fight(target) => {
+                let enemy = &self.enemies[target];
+                let hit = roll_hit(
+                    rng,
+                    progress.accuracy(),
+                    enemy.enemy.evasion,
+                    progress.crit_chance(),
+                );
+                let damage = match hit {
+                    Hit::Normal => enemy.attack_damage,
+                    Hit::Critical => (enemy.attack_damage as f32 * CRIT_DAMAGE_FACTOR) as i32,
+                    Hit::Miss => 0,
+                };
+                self.damage_enemy(progress, target, damage);
+                PlayerOutcome::Attack {
+                    target,
+                    hit,
+                    damage,
+                }
+            }
+
+            PlayerAction::Magic(choice, target) => {
+                let magic = progress.magic[choice].magic;
+                progress.mp -= magic.mp_cost();
+                progress.stats.spells_cast += 1;
+
+                match magic {
+                    Magic::Heal => {
+                        let heal_amount = (progress.max_hp + 1) / 2;
+                        progress.hp = progress.max_hp.min(progress.hp + heal_amount);
+                        PlayerOutcome::Heal { hp: heal_amount }
+                    }
+
+                    Magic::EarthEdge | Magic::WaterEdge | Magic::FireEdge => {
+                        self.follow_up = Some((magic, 1));
+                        let enemy = &self.enemies[target].enemy;
+                        let affinity = enemy.affinity(magic);
+                        let mut damage =
+                            calc_magic_damage(rng, progress.attack, enemy, self.follow_up);
+                        if self.player_statuses.has(Status::Weaken) {
+                            damage = (damage / 2).max(1);
+                        }
+                        let damage = match affinity {
+                            Affinity::Immune => 0,
+                            Affinity::Absorb => {
+                                let enemy = &mut self.enemies[target];
+                                let healed = damage.min(enemy.max_hp - enemy.enemy.hp);
+                                enemy.enemy.hp += healed;
+                                healed
+                            }
+                            _ => {
+                                self.damage_enemy(progress, target, damage);
+                                damage
+                            }
+                        };
+
+                        let enemy = &mut self.enemies[target];
+                        progress
+                            .bestiary_entry_mut(&enemy.enemy.name)
+                            .known_affinities
+                            .insert(magic);
+
+                        let status = match magic {
+                            Magic::EarthEdge => Status::Weaken,
+                            Magic::WaterEdge => Status::Sleep,
+                            _ => Status::Poison,
+                        };
+                        let harmed = !matches!(affinity, Affinity::Immune | Affinity::Absorb);
+                        let inflicted = (harmed
+                            && rng.random(100) < EDGE_STATUS_CHANCE
+                            && !enemy.enemy.status_immunities.contains(&status))
+                        .then(|| {
+                            enemy.statuses.inflict(status, EDGE_STATUS_TURNS);
+                            status
+                        });
+
+                        PlayerOutcome::Spell {
+                            target,
+                            magic,
+                            damage,
+                            affinity,
+                            inflicted,
+                        }
+                    }
+                }
+            }
+
+            PlayerAction::Item(choice, target) => {
+                progress.items[choice].amount -= 1;
+                progress.stats.items_used += 1;
+
+                let item = progress.items[choice].item;
+                if let Item::Lens = item {
+                    progress
+                        .bestiary_entry_mut(&self.enemies[target].enemy.name)
+                        .known_affinities
+                        .extend(Magic::EDGES);
+                    return PlayerOutcome::Scan { target };
+                }
+
+                let (heal_hp, heal_mp) = match item {
+                    Item::Salve => ((progress.max_hp * 3 + 9) / 10, 0),
+                    Item::XSalve => (progress.max_hp, 0),
+                    Item::Tonic => (0, (progress.max_mp * 3 + 9) / 10),
+                    Item::XTonic => (0, progress.max_mp),
+                    Item::Remedy | Item::Lens => (0, 0),
+                };
+                progress.hp = progress.max_hp.min(progress.hp + heal_hp);
+                progress.mp = progress.max_mp.min(progress.mp + heal_mp);
+
+                let cured = matches!(item, Item::Remedy);
+                if cured {
+                    self.player_statuses = StatusEffects::default();
+                }
+
+                PlayerOutcome::Item {
+                    item,
+                    hp: heal_hp,
+                    mp: heal_mp,
+                    cured,
+                }
+            }
+
+            PlayerAction::Defend => {
+                self.defending = true;
+                let mp = (progress.max_mp + 9) / 10;
+                progress.mp = progress.max_mp.min(progress.mp + mp);
+                PlayerOutcome::Defend { mp }
+            }
+
+            PlayerAction::Run => {
+                // The more damaged the enemies are, the easier it is to get away.
+                let max_hp: i32 = self.enemies.iter().map(|e| e.max_hp).sum();
+                let hp: i32 = self.enemies.iter().map(|e| e.enemy.hp.max(0)).sum();
+                let attack_damage = self
+                    .enemies
+                    .iter()
+                    .filter(|e| !e.defeated())
+                    .map(|e| e.attack_damage)
+                    .max()
+                    .unwrap_or(0);
+                let hp_run_threshold = max_hp - hp + attack_damage;
+                if rng.random(max_hp as u32) < hp_run_threshold as u32 {
+                    PlayerOutcome::RanAway
+                } else {
+                    self.fleeing = true;
+                    PlayerOutcome::RunFailed
+                }
+            }
+        }
+    }
+
+    // Each level Coric has over the strongest enemy makes a preemptive strike more likely and an
+    // ambush less likely, and vice versa.
+    pub fn roll_opening(&self, rng: &mut Rng, progress: &Progress) -> Opening {
+        let strongest = self
+            .enemies
+            .iter()
+            .map(|e| e.enemy.level)
+            .max()
+            .unwrap_or(1);
+        let initiative = 2 * (progress.level - strongest) + progress.initiative();
+        let preemptive_chance = (BASE_OPENING_CHANCE + initiative).clamp(1, MAX_OPENING_CHANCE);
+        let ambush_chance = (BASE_OPENING_CHANCE - initiative).clamp(1, MAX_OPENING_CHANCE);
+
+        let roll = rng.random(100) as i32;
+        if roll < preemptive_chance {
+            Opening::Preemptive
+        } else if roll < preemptive_chance + ambush_chance {
+            Opening::Ambush
+        } else {
+            Opening::Normal
+        }
+    }
+
+    // Call before each player turn.
+    pub fn start_turn(&mut self, rng: &mut Rng, progress: &Progress) {
+        // Defending doesn't use up follow-up magic, so it waits for the next turn instead.
+        if self.defending {
+            self.defending = false;
+        } else if let Some((magic, turns)) = self.follow_up {
+            self.follow_up = if turns > 0 {
+                Some((magic, turns - 1))
+            } else {
+                None
+            };
+        }
+
+        for enemy in self.enemies.iter_mut().filter(|e| !e.defeated()) {
+            enemy.attack_damage =
+                calc_magic_damage(rng, progress.attack, &enemy.enemy, self.follow_up);
+            if self.player_statuses.has(Status::Weaken) {
+                enemy.attack_damage = (enemy.attack_damage / 2).max(1);
+            }
+        }
+    }
+}
+
+impl BattleEnemy {
+    // The phase the enemy is currently in, if it has changed phase at all.
+    pub fn current_phase(&self) -> Option<&EnemyPhase> {
+        self.phase.checked_sub(1).map(|i| &self.enemy.phases[i])
+    }
+
+    pub fn defeated(&self) -> bool {
+        self.enemy.hp <= 0
+    }
+}
+
+impl StatusEffects {
+    // Counts down a turn of every status, returning those that wore off.
+    fn count_down(&mut self) -> Vec<Status> {
+        let mut wore_off = Vec::new();
+        for status in Status::ALL {
+            let turns_left = &mut self.turns_left[status as usize];
+            if *turns_left > 0 {
+                *turns_left -= 1;
+                if *turns_left == 0 {
+                    wore_off.push(status);
+                }
+            }
+        }
+        wore_off
+    }
+
+    fn cure(&mut self, status: Status) {
+        self.turns_left[status as usize] = 0;
+    }
+
+    pub fn has(&self, status: Status) -> bool {
+        self.turns_left[status as usize] > 0
+    }
+
+    // Inflicting a status that's already active just renews it.
+    fn inflict(&mut self, status: Status, turns: u32) {
+        let turns_left = &mut self.turns_left[status as usize];
+        *turns_left = (*turns_left).max(turns);
+    }
+}
+
+fn calc_base_damage(attack: i32, defense: i32) -> f32 {
+    let attack = attack as f32;
+    let defense = defense as f32;
+    if attack * attack < defense {
+        1.0
+    } else if attack < defense {
+        attack * attack / defense
+    } else {
+        attack * 2.0 - defense
+    }
+}
+
+fn calc_magic_damage(
+    rng: &mut Rng,
+    attack: i32,
+    enemy: &Enemy,
+    follow_up: Option<(Magic, usize)>,
+) -> i32 {
+    let base_damage = calc_base_damage(attack, enemy.defense);
+    let bonus: f32 = match follow_up {
+        Some((magic, turns)) => {
+            // Weaknesses raise the bonus a tier and resistances lower it one.
+            let tier = match enemy.affinity(magic) {
+                Affinity::Weak => turns + 2,
+                Affinity::Normal => turns + 1,
+                Affinity::Resist => turns,
+                Affinity::Immune | Affinity::Absorb => 0,
+            };
+            [0.0, 0.5, 1.0, 2.0][tier.min(3)]
+        }
+        None => 0.0,
+    };
+    let damage = base_damage * (1.0 + bonus);
+    damage.trunc() as i32
+        + if (rng.random(100) as f32) < damage.fract() * 100.0 {
+            1
+        } else {
+            0
+        }
+}
+
+// Rolls whether an attack lands, and if so, whether it's a critical hit.
+fn roll_hit(rng: &mut Rng, accuracy: i32, evasion: i32, crit_chance: i32) -> Hit {
+    if rng.random(100) as i32 >= accuracy - evasion {
+        Hit::Miss
+    } else if (rng.random(100) as i32) < crit_chance {
+        Hit::Critical
+    } else {
+        Hit::Normal
+    }
+}
@@ -0,0 +1,427 @@
+// Inspect and edit Coric's Quest save data outside of the game.
+//
+// This shares the game's own progress module, so save data is decoded, migrated and encoded
+// exactly as the game would do it.
+
+#[allow(dead_code)]
+#[path = "../backdrop.rs"]
+mod backdrop;
+#[allow(dead_code)]
+#[path = "../direction.rs"]
+mod direction;
+#[allow(dead_code)]
+#[path = "../enemy.rs"]
+mod enemy;
+#[allow(dead_code)]
+#[path = "../progress/mod.rs"]
+mod progress;
+#[allow(dead_code)]
+#[path = "../random.rs"]
+mod random;
+#[allow(dead_code)]
+#[path = "../storage.rs"]
+mod storage;
+
+use direction::*;
+use progress::*;
+
+use miniserde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::process::ExitCode;
+
+const USAGE: &str = "\
+Usage: cqsave <command> <file> [options]
+
+<file> is either a local.data storage file or a raw save data blob.
+
+Commands:
+  show                 Print the decoded save data.
+  check                Report problems that the game would repair on load.
+  set <field>=<value>  Change one or more fields and write the save data.
+  upgrade              Write the save data in the latest save version.
+
+Options:
+  --slot <n>       Save slot to use in a local.data file (1 to 3, default 1).
+  --output <path>  Write to <path> instead of overwriting <file>.
+
+Fields:
+  hp, max_hp, mp, max_mp, attack, defense, level, exp, base_exp  (numbers)
+  flag.<name>                        (true/false, e.g. flag.earth_defeated=true)
+  var.<name>                         (number)
+  weapon, armor                      (<name>:<bonus> or none)
+  item.<name>                        (amount, e.g. item.Salve=3)
+  magic.<name>                       (known, e.g. magic.Heal=true)
+  steps.<n>                          (steps for encounter group <n>)
+  location                           (<level>,<x>,<y>,<north|east|south|west>)
+  collected_chests, turned_levers    (comma-separated entity iids)";
+
+// The on-disk format that quad_storage uses for native builds.
+#[derive(Deserialize, Serialize)]
+struct LocalData {
+    local: BTreeMap<String, String>,
+}
+
+enum SaveFile {
+    Local { data: LocalData, slot: usize },
+    Raw(String),
+}
+
+enum Command {
+    Show,
+    Check,
+    Set(Vec<(String, String)>),
+    Upgrade,
+}
+
+impl SaveFile {
+    fn read(path: &str, slot: usize) -> Result<Self, String> {
+        let contents =
+            std::fs::read_to_string(path).map_err(|e| format!("couldn't read {path}: {e}"))?;
+        if contents.trim_start().starts_with('{') {
+            let data = miniserde::json::from_str(&contents)
+                .map_err(|_| format!("{path} is not a valid local.data file"))?;
+            Ok(Self::Local { data, slot })
+        } else {
+            Ok(Self::Raw(contents))
+        }
+    }
+
+    fn raw_save_data(&self) -> Result<&str, String> {
+        match self {
+            Self::Local { data, slot } => data
+                .local
+                .get(&slot_key(SAVE_KEY, *slot))
+                .map(String::as_str)
+                .ok_or_else(|| format!("no save data in slot {}", slot + 1)),
+            Self::Raw(raw_data) => Ok(raw_data),
+        }
+    }
+
+    fn raw_summary(&self) -> Option<&str> {
+        match self {
+            Self::Local { data, slot } => data
+                .local
+                .get(&slot_key(SAVE_SUMMARY_KEY, *slot))
+                .map(String::as_str),
+            Self::Raw(_) => None,
+        }
+    }
+
+    fn write(&mut self, path: &str, progress: &Progress) -> Result<(), String> {
+        let raw_data = progress.to_save_data();
+        let contents = match self {
+            Self::Local { data, slot } => {
+                data.local.insert(slot_key(SAVE_KEY, *slot), raw_data);
+                // Keep the slot picker in step with the edited level and play time.
+                let summary_key = slot_key(SAVE_SUMMARY_KEY, *slot);
+                if let Some(raw_summary) = data.local.get(&summary_key) {
+                    if let Ok(mut summary) =
+                        miniserde::json::from_str::<SaveSlotSummary>(raw_summary)
+                    {
+                        summary.level = progress.level;
+                        summary.play_time = progress.stats.play_time;
+                        data.local
+                            .insert(summary_key, miniserde::json::to_string(&summary));
+                    }
+                }
+                miniserde::json::to_string(data)
+            }
+            Self::Raw(_) => raw_data,
+        };
+        std::fs::write(path, contents).map_err(|e| format!("couldn't write {path}: {e}"))
+    }
+}
+
+fn main() -> ExitCode {
+    match run(std::env::args().skip(1).collect()) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("cqsave: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn direction_name(dir: Direction) -> &'static str {
+    match dir {
+        Direction::North => "north",
+        Direction::East => "east",
+        Direction::South => "south",
+        Direction::West => "west",
+    }
+}
+
+fn parse_args(args: Vec<String>) -> Result<(Command, String, usize, Option<String>), String> {
+    let mut args = args.into_iter();
+    let command = args.next().ok_or(USAGE)?;
+    let path = args.next().ok_or(USAGE)?;
+    let mut slot = 0;
+    let mut output = None;
+    let mut assignments = Vec::new();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--slot" => {
+                let n = args.next().ok_or("--slot needs a value")?;
+                slot = match n.parse::<usize>() {
+                    Ok(n) if (1..=NUM_SAVE_SLOTS).contains(&n) => n - 1,
+                    _ => return Err(format!("invalid slot: {n}")),
+                };
+            }
+            "--output" => output = Some(args.next().ok_or("--output needs a value")?),
+            _ if command == "set" => {
+                let (field, value) = arg
+                    .split_once('=')
+                    .ok_or_else(|| format!("expected <field>=<value>, got {arg}"))?;
+                assignments.push((field.to_string(), value.to_string()));
+            }
+            _ => return Err(format!("unexpected argument: {arg}")),
+        }
+    }
+
+    let command = match command.as_str() {
+        "show" => Command::Show,
+        "check" => Command::Check,
+        "set" if assignments.is_empty() => return Err(String::from("nothing to set")),
+        "set" => Command::Set(assignments),
+        "upgrade" => Command::Upgrade,
+        "help" | "--help" | "-h" => return Err(String::from(USAGE)),
+        _ => return Err(format!("unknown command: {command}\n\n{USAGE}")),
+    };
+
+    Ok((command, path, slot, output))
+}
+
+fn parse_value<T: std::str::FromStr>(field: &str, value: &str) -> Result<T, String> {
+    value
+        .parse()
+        .map_err(|_| format!("invalid value for {field}: {value}"))
+}
+
+fn print_progress(progress: &Progress) {
+    println!(
+        "Level {} ({}), EXP {} (base {}, next {})",
+        progress.level,
+        player_rank(progress.level),
+        progress.exp,
+        progress.base_exp,
+        // `next_exp` panics on levels below 1, which hand-edited saves may have.
+        Some(progress.level)
+            .filter(|&level| level >= 1)
+            .and_then(|_| progress.next_exp())
+            .map_or(String::from("-"), |next_exp| next_exp.to_string()),
+    );
+    println!(
+        "HP {}/{}, MP {}/{}, Attack {}, Defense {}",
+        progress.hp,
+        progress.max_hp,
+        progress.mp,
+        progress.max_mp,
+        progress.attack,
+        progress.defense,
+    );
+    match &progress.weapon {
+        Some(weapon) => println!("Weapon: {} (+{})", weapon.name, weapon.attack),
+        None => println!("Weapon: none"),
+    }
+    match &progress.armor {
+        Some(armor) => println!("Armor: {} (+{})", armor.name, armor.defense),
+        None => println!("Armor: none"),
+    }
+    println!("Items:");
+    for slot in &progress.items {
+        println!("  {:8} {}", slot.item.name(), slot.amount);
+    }
+    println!("Magic:");
+    for slot in &progress.magic {
+        println!(
+            "  {:10} {}",
+            slot.magic.name(),
+            if slot.known { "known" } else { "unknown" }
+        );
+    }
+    println!(
+        "Location: {} ({}, {}) facing {}",
+        progress.location.level_id,
+        progress.location.x,
+        progress.location.y,
+        direction_name(progress.location.dir),
+    );
+    println!("Flags:");
+    for name in progress.flag_names() {
+        println!("  {name} = {}", progress.flag(&name));
+    }
+    println!("Vars:");
+    for name in progress.var_names() {
+        println!("  {name} = {}", progress.var(&name));
+    }
+    println!("Steps: {:?}", progress.steps);
+    println!("Collected chests: {}", progress.collected_chests.join(", "));
+    println!("Turned levers: {}", progress.turned_levers.join(", "));
+    let stats = &progress.stats;
+    println!("Play time: {}", format_play_time(stats.play_time));
+    println!(
+        "Battles: {} fought, {} won, {} fled, {} lost",
+        stats.battles_fought, stats.battles_won, stats.battles_fled, stats.battles_lost,
+    );
+    println!(
+        "Damage: {} dealt, {} taken",
+        stats.damage_dealt, stats.damage_taken,
+    );
+    println!(
+        "Spells cast: {}, items used: {}, deaths: {}",
+        stats.spells_cast, stats.items_used, stats.deaths,
+    );
+    println!("Bestiary:");
+    for (name, entry) in &progress.bestiary {
+        let known: Vec<&str> = entry.known_affinities.iter().map(|m| m.name()).collect();
+        println!(
+            "  {name:12} seen {}, defeated {}{}",
+            entry.seen,
+            entry.defeated,
+            if known.is_empty() {
+                String::new()
+            } else {
+                format!(", affinities known: {}", known.join(", "))
+            },
+        );
+    }
+}
+
+fn run(args: Vec<String>) -> Result<(), String> {
+    let (command, path, slot, output) = parse_args(args)?;
+    let mut save_file = SaveFile::read(&path, slot)?;
+    let raw_data = save_file.raw_save_data()?;
+    let mut progress =
+        Progress::from_slot_data(raw_data, save_file.raw_summary()).map_err(|e| e.to_string())?;
+
+    match command {
+        Command::Show => print_progress(&progress),
+        Command::Check => {
+            let repairs = progress.repair().map_err(|e| e.to_string())?;
+            if repairs.is_empty() {
+                println!("No problems found.");
+            }
+            for repair in repairs {
+                println!("{repair}");
+            }
+        }
+        Command::Set(assignments) => {
+            for (field, value) in assignments {
+                set_field(&mut progress, &field, &value)?;
+            }
+            save_file.write(output.as_ref().unwrap_or(&path), &progress)?;
+        }
+        Command::Upgrade => {
+            save_file.write(output.as_ref().unwrap_or(&path), &progress)?;
+            println!("Wrote save version {SAVE_VERSION}.");
+        }
+    }
+
+    Ok(())
+}
+
+fn set_field(progress: &mut Progress, field: &str, value: &str) -> Result<(), String> {
+    match field {
+        "hp" => progress.hp = parse_value(field, value)?,
+        "max_hp" => progress.max_hp = parse_value(field, value)?,
+        "mp" => progress.mp = parse_value(field, value)?,
+        "max_mp" => progress.max_mp = parse_value(field, value)?,
+        "attack" => progress.attack = parse_value(field, value)?,
+        "defense" => progress.defense = parse_value(field, value)?,
+        "level" => progress.level = parse_value(field, value)?,
+        "exp" => progress.exp = parse_value(field, value)?,
+        "base_exp" => progress.base_exp = parse_value(field, value)?,
+        "weapon" => {
+            progress.weapon = match value {
+                "none" => None,
+                _ => {
+                    let (name, attack) = value
+                        .rsplit_once(':')
+                        .ok_or_else(|| format!("expected <name>:<attack> for weapon: {value}"))?;
+                    Some(Weapon {
+                        name: name.to_string(),
+                        attack: parse_value(field, attack)?,
+                    })
+                }
+            }
+        }
+        "armor" => {
+            progress.armor = match value {
+                "none" => None,
+                _ => {
+                    let (name, defense) = value
+                        .rsplit_once(':')
+                        .ok_or_else(|| format!("expected <name>:<defense> for armor: {value}"))?;
+                    Some(Armor {
+                        name: name.to_string(),
+                        defense: parse_value(field, defense)?,
+                    })
+                }
+            }
+        }
+        "location" => {
+            let parts: Vec<&str> = value.split(',').collect();
+            let [level_id, x, y, dir] = parts[..] else {
+                return Err(format!(
+                    "expected <level>,<x>,<y>,<dir> for location: {value}"
+                ));
+            };
+            progress.location = Location {
+                level_id: level_id.to_string(),
+                x: parse_value(field, x)?,
+                y: parse_value(field, y)?,
+                dir: match dir {
+                    "north" => Direction::North,
+                    "east" => Direction::East,
+                    "south" => Direction::South,
+                    "west" => Direction::West,
+                    _ => return Err(format!("invalid direction: {dir}")),
+                },
+            };
+        }
+        "collected_chests" | "turned_levers" => {
+            let list = value
+                .split(',')
+                .filter(|s| !s.is_empty())
+                .map(String::from)
+                .collect();
+            if field == "collected_chests" {
+                progress.collected_chests = list;
+            } else {
+                progress.turned_levers = list;
+            }
+        }
+        _ => {
+            if let Some(name) = field.strip_prefix("item.") {
+                let slot = progress
+                    .items
+                    .iter_mut()
+                    .find(|s| s.item.name().eq_ignore_ascii_case(name))
+                    .ok_or_else(|| format!("no item slot named {name}"))?;
+                slot.amount = parse_value(field, value)?;
+            } else if let Some(name) = field.strip_prefix("magic.") {
+                let slot = progress
+                    .magic
+                    .iter_mut()
+                    .find(|s| s.magic.name().eq_ignore_ascii_case(name))
+                    .ok_or_else(|| format!("no magic slot named {name}"))?;
+                slot.known = parse_value(field, value)?;
+            } else if let Some(name) = field.strip_prefix("flag.") {
+                progress.set_flag(name, parse_value(field, value)?);
+            } else if let Some(name) = field.strip_prefix("var.") {
+                progress.set_var(name, parse_value(field, value)?);
+            } else if let Some(index) = field.strip_prefix("steps.") {
+                let index: usize = parse_value(field, index)?;
+                let steps = progress
+                    .steps
+                    .get_mut(index)
+                    .ok_or_else(|| format!("no steps entry {index}"))?;
+                *steps = parse_value(field, value)?;
+            } else {
+                return Err(format!("unknown field: {field}"));
+            }
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,345 @@
+// Simulate Coric's Quest battles without playing them, for balance testing.
+//
+// This shares the game's own battle engine, so fights are resolved exactly as the game would do
+// it; only the player's choices are made by a simple fixed strategy instead.
+
+#[allow(dead_code)]
+#[path = "../backdrop.rs"]
+mod backdrop;
+#[allow(dead_code)]
+#[path = "../battle_engine.rs"]
+mod battle_engine;
+#[allow(dead_code)]
+#[path = "../direction.rs"]
+mod direction;
+#[allow(dead_code)]
+#[path = "../enemy.rs"]
+mod enemy;
+#[allow(dead_code)]
+#[path = "../progress/mod.rs"]
+mod progress;
+#[allow(dead_code)]
+#[path = "../random.rs"]
+mod random;
+#[allow(dead_code)]
+#[path = "../storage.rs"]
+mod storage;
+
+use battle_engine::*;
+use enemy::*;
+use progress::*;
+use random::*;
+
+use std::process::ExitCode;
+
+const USAGE: &str = "\
+Usage: cqsim [options] [target...]
+
+Targets are encounter groups (Wilderness1, Wilderness2, Wilderness3, EarthCastle, WaterCastle,
+FireCastle) or bosses (Earth, Water, Fire).  With no targets, every one of them is simulated.
+
+Options:
+  --level <n>      Coric's level (default 1).
+  --weapon <n>     Attack bonus of Coric's weapon (default none).
+  --armor <n>      Defense bonus of Coric's armor (default none).
+  --items <n>      Amount of each item Coric carries (default 0).
+  --magic <list>   Comma-separated magic Coric knows, e.g. Heal,FireEdge, or all.
+  --fights <n>     Fights to simulate per target (default 1000).
+  --seed <n>       Random seed (default 1).
+
+Coric heals below a third of max HP if possible, uses a Remedy when poisoned or silenced, casts
+magic an enemy is weak to when it isn't already following up, and attacks the enemy with the
+least HP otherwise.  Coric never defends or runs.  Encounter groups get preemptive strikes and
+ambushes as often as random encounters would in the game.
+
+Debug builds use enemies.json in the working directory instead of the built-in enemies if it
+exists, so rebalanced enemies can be tried out without recompiling.";
+
+const GROUPS: &[&str] = &[
+    "Wilderness1",
+    "Wilderness2",
+    "Wilderness3",
+    "EarthCastle",
+    "WaterCastle",
+    "FireCastle",
+];
+
+const BOSSES: &[&str] = &["Earth", "Water", "Fire"];
+
+// Fights that go on longer than this are counted as losses.
+const MAX_TURNS: u32 = 1000;
+
+struct Options {
+    level: i32,
+    weapon: i32,
+    armor: i32,
+    items: i32,
+    magic: Vec<Magic>,
+    fights: u32,
+    seed: u64,
+    targets: Vec<String>,
+}
+
+struct FightResult {
+    won: bool,
+    turns: u32,
+    hp_lost: u32,
+}
+
+fn choose_action(engine: &BattleEngine, progress: &Progress) -> PlayerAction {
+    let castable = |magic: Magic| {
+        progress.magic.iter().position(|m| {
+            m.magic == magic
+                && m.known
+                && progress.mp >= magic.mp_cost()
+                && !engine.player_statuses.has(Status::Silence)
+        })
+    };
+    let usable = |item: Item| {
+        progress
+            .items
+            .iter()
+            .position(|i| i.item == item && i.amount > 0)
+    };
+
+    if progress.hp * 3 < progress.max_hp {
+        if let Some(choice) = castable(Magic::Heal) {
+            return PlayerAction::Magic(choice, 0);
+        }
+        if let Some(choice) = usable(Item::Salve).or_else(|| usable(Item::XSalve)) {
+            return PlayerAction::Item(choice, 0);
+        }
+    }
+
+    if engine.player_statuses.has(Status::Poison) || engine.player_statuses.has(Status::Silence) {
+        if let Some(choice) = usable(Item::Remedy) {
+            return PlayerAction::Item(choice, 0);
+        }
+    }
+
+    let targets = || (0..engine.enemies.len()).filter(|&i| !engine.enemies[i].defeated());
+
+    if engine.follow_up.is_none() {
+        for target in targets() {
+            let enemy = &engine.enemies[target].enemy;
+            for magic in [Magic::EarthEdge, Magic::WaterEdge, Magic::FireEdge] {
+                if enemy.affinity(magic) == Affinity::Weak {
+                    if let Some(choice) = castable(magic) {
+                        return PlayerAction::Magic(choice, target);
+                    }
+                }
+            }
+        }
+    }
+
+    let weakest = targets()
+        .min_by_key(|&i| engine.enemies[i].enemy.hp)
+        .expect("enemy to attack");
+    PlayerAction::Fight(weakest)
+}
+
+fn main() -> ExitCode {
+    match run(std::env::args().skip(1).collect()) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("cqsim: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn new_progress(options: &Options) -> Progress {
+    let mut progress = Progress::new();
+    for _ in 1..options.level {
+        progress.gain_level();
+    }
+    progress.maybe_upgrade_weapon("Weapon", options.weapon);
+    progress.maybe_upgrade_armor("Armor", options.armor);
+    for slot in &mut progress.items {
+        slot.amount = options.items;
+    }
+    for slot in &mut progress.magic {
+        slot.known = options.magic.contains(&slot.magic);
+    }
+    progress
+}
+
+fn parse_args(args: Vec<String>) -> Result<Options, String> {
+    let mut args = args.into_iter();
+    let mut options = Options {
+        level: 1,
+        weapon: 0,
+        armor: 0,
+        items: 0,
+        magic: Vec::new(),
+        fights: 1000,
+        seed: 1,
+        targets: Vec::new(),
+    };
+
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or_else(|| format!("{arg} needs a value"));
+        match arg.as_str() {
+            "--level" => options.level = parse_value(&arg, &value()?)?,
+            "--weapon" => options.weapon = parse_value(&arg, &value()?)?,
+            "--armor" => options.armor = parse_value(&arg, &value()?)?,
+            "--items" => options.items = parse_value(&arg, &value()?)?,
+            "--magic" => {
+                let all = [
+                    Magic::Heal,
+                    Magic::EarthEdge,
+                    Magic::WaterEdge,
+                    Magic::FireEdge,
+                ];
+                let list = value()?;
+                options.magic = if list == "all" {
+                    all.to_vec()
+                } else {
+                    list.split(',')
+                        .map(|name| {
+                            all.into_iter()
+                                .find(|m| m.name().eq_ignore_ascii_case(name))
+                                .ok_or_else(|| format!("unknown magic: {name}"))
+                        })
+                        .collect::<Result<_, _>>()?
+                };
+            }
+            "--fights" => options.fights = parse_value(&arg, &value()?)?,
+            "--seed" => options.seed = parse_value(&arg, &value()?)?,
+            "help" | "--help" | "-h" => return Err(String::from(USAGE)),
+            _ if arg.starts_with("--") => return Err(format!("unknown option: {arg}\n\n{USAGE}")),
+            _ => {
+                if !GROUPS.contains(&arg.as_str()) && !BOSSES.contains(&arg.as_str()) {
+                    return Err(format!("unknown target: {arg}"));
+                }
+                options.targets.push(arg);
+            }
+        }
+    }
+
+    if !(1..=30).contains(&options.level) {
+        return Err(format!("level must be from 1 to 30: {}", options.level));
+    }
+    if !(0..=MAX_ITEM_AMOUNT).contains(&options.items) {
+        return Err(format!(
+            "items must be from 0 to {MAX_ITEM_AMOUNT}: {}",
+            options.items
+        ));
+    }
+    if options.fights == 0 {
+        return Err(String::from("fights must be at least 1"));
+    }
+    if options.targets.is_empty() {
+        options.targets = GROUPS
+            .iter()
+            .chain(BOSSES)
+            .map(|name| name.to_string())
+            .collect();
+    }
+
+    Ok(options)
+}
+
+fn parse_value<T: std::str::FromStr>(option: &str, value: &str) -> Result<T, String> {
+    value
+        .parse()
+        .map_err(|_| format!("invalid value for {option}: {value}"))
+}
+
+fn run(args: Vec<String>) -> Result<(), String> {
+    let options = parse_args(args)?;
+    let enemies = EnemySet::new();
+    let mut rng = Rng::new(options.seed);
+
+    println!(
+        "{:12} {:>8} {:>10} {:>11}",
+        "Target", "Win rate", "Avg turns", "Avg HP lost"
+    );
+    for target in &options.targets {
+        let mut wins = 0;
+        let mut total_turns = 0;
+        let mut total_hp_lost = 0;
+
+        for _ in 0..options.fights {
+            let boss_fight = BOSSES.contains(&target.as_str());
+            let formation = if boss_fight {
+                vec![enemies.by_name(target)]
+            } else {
+                enemies.random_formation(EncounterGroup::from(target.as_str()), &mut rng)
+            };
+            let result = simulate_fight(&mut rng, new_progress(&options), formation, boss_fight);
+            if result.won {
+                wins += 1;
+            }
+            total_turns += u64::from(result.turns);
+            total_hp_lost += u64::from(result.hp_lost);
+        }
+
+        let fights = f64::from(options.fights);
+        println!(
+            "{:12} {:>7.1}% {:>10.1} {:>11.1}",
+            target,
+            f64::from(wins) * 100.0 / fights,
+            total_turns as f64 / fights,
+            total_hp_lost as f64 / fights,
+        );
+    }
+
+    Ok(())
+}
+
+fn simulate_fight(
+    rng: &mut Rng,
+    mut progress: Progress,
+    formation: Vec<Enemy>,
+    boss_fight: bool,
+) -> FightResult {
+    let mut engine = BattleEngine::new(formation);
+    let mut turns = 0;
+    let mut opening = if boss_fight {
+        Opening::Normal
+    } else {
+        engine.roll_opening(rng, &progress)
+    };
+
+    let won = loop {
+        if turns == MAX_TURNS {
+            break false;
+        }
+        turns += 1;
+
+        engine.start_turn(rng, &progress);
+        if opening != Opening::Ambush && !engine.player_asleep() {
+            let action = choose_action(&engine, &progress);
+            if let PlayerOutcome::RanAway = engine.player_turn(rng, &mut progress, action) {
+                break false;
+            }
+        }
+        if engine.all_enemies_defeated() {
+            break true;
+        }
+
+        for i in 0..engine.enemies.len() {
+            if !engine.enemies[i].defeated() && opening != Opening::Preemptive {
+                engine.enemy_turn(rng, &mut progress, i);
+            }
+        }
+        opening = Opening::Normal;
+        if progress.hp <= 0 {
+            break false;
+        }
+
+        engine.end_turn(&mut progress);
+        if progress.hp <= 0 {
+            break false;
+        } else if engine.all_enemies_defeated() {
+            break true;
+        }
+    };
+
+    FightResult {
+        won,
+        turns,
+        hp_lost: progress.stats.damage_taken,
+    }
+}
@@ -10,7 +10,11 @@ use crate::levels::*;
 use crate::modes::*;
 use crate::progress::*;
 use crate::random::*;
+use crate::replay::*;
 use crate::resources::*;
+use crate::saved_options::*;
+use crate::screen_shake::*;
+use crate::storage::*;
 
 use miniquad::GlContext;
 
@@ -30,7 +34,9 @@ macro_rules! update_mode {
                     level: &self.level,
                     actors: &mut self.actors,
                     fade: &mut self.fade,
+                    screen_shake: &mut self.screen_shake,
                     encounter_steps: &mut self.encounter_steps,
+                    battle_speed: &mut self.battle_speed,
                 })
                 .await
         }
@@ -53,7 +59,9 @@ pub struct ModeContext<'a, 'g> {
     pub level: &'a SharedMut<Level>,
     pub actors: &'a mut SharedMut<Vec<Actor>>,
     pub fade: &'a mut SharedMut<Fade>,
+    pub screen_shake: &'a mut SharedMut<ScreenShake>,
     pub encounter_steps: &'a mut i32,
+    pub battle_speed: &'a mut BattleSpeed,
 }
 
 pub struct ScriptContext {
@@ -66,11 +74,17 @@ pub struct ScriptContext {
     pub level: SharedMut<Level>,
     pub actors: SharedMut<Vec<Actor>>,
     pub fade: SharedMut<Fade>,
+    pub screen_shake: SharedMut<ScreenShake>,
     pub encounter_steps: i32,
-    pub confirm_save_overwrite: bool,
+    pub battle_speed: BattleSpeed,
+    // Set up by pushing a battle mode, to be taken when the battle is run.
+    pub battle_recorder: Option<SharedMut<BattleRecorder>>,
+    pub confirm_save_overwrite: [bool; NUM_SAVE_SLOTS],
+    pub play_time_start: f64,
 }
 
 impl ScriptContext {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         res: Resources,
         input: &SharedMut<Input>,
@@ -79,6 +93,7 @@ impl ScriptContext {
         level: &SharedMut<Level>,
         actors: &SharedMut<Vec<Actor>>,
         fade: &SharedMut<Fade>,
+        screen_shake: &SharedMut<ScreenShake>,
     ) -> Self {
         let mut rng = Rng::new(miniquad::date::now() as _);
         let encounter_steps = 15 + rng.random(26) as i32;
@@ -96,8 +111,12 @@ impl ScriptContext {
                 level: SharedMut::clone(level),
                 actors: SharedMut::clone(actors),
                 fade: SharedMut::clone(fade),
+                screen_shake: SharedMut::clone(screen_shake),
                 encounter_steps,
-                confirm_save_overwrite: true,
+                battle_speed: BattleSpeed::new(),
+                battle_recorder: None,
+                confirm_save_overwrite: [true; NUM_SAVE_SLOTS],
+                play_time_start: miniquad::date::now(),
             }
         }
     }
@@ -105,24 +124,20 @@ impl ScriptContext {
     fn prepare_level_and_actors(&self, level: &mut Level, actors: &mut [Actor]) {
         let gctx = get_gctx();
 
-        // display chest according to open/closed state in progress
-        if let Some(chest) = actors.iter_mut().find(|a| a.identifier == ActorType::Chest) {
-            let chest_opened = self
-                .progress
-                .collected_chests
-                .iter()
-                .map(String::as_str)
-                .any(|s| s == level.identifier);
+        // display chests according to open/closed state in progress
+        for chest in actors
+            .iter_mut()
+            .filter(|a| a.identifier == ActorType::Chest)
+        {
+            let chest_opened = chest
+                .iid
+                .as_ref()
+                .is_some_and(|iid| self.progress.collected_chests.contains(iid));
             chest.start_animation(if chest_opened { "open" } else { "closed" });
         }
 
-        // show lever turned to its last position and update the map tiles it controls
-        let lever_turned = self
-            .progress
-            .turned_levers
-            .iter()
-            .any(|l| l == level.identifier.as_str());
-        sync_level_and_actors_with_lever(gctx, lever_turned, level, actors);
+        // show levers turned to their last positions and update the map tiles they control
+        sync_level_and_actors_with_levers(gctx, &self.progress.turned_levers, level, actors);
     }
 
     pub fn level_by_identifier(&self, identifier: &str) -> (Level, Vec<Actor>) {
@@ -162,38 +177,60 @@ impl ScriptContext {
             })
     }
 
-    pub fn lever_is_turned(&self) -> bool {
-        self.progress
-            .turned_levers
-            .iter()
-            .any(|l| l == self.level.identifier.as_str())
+    pub fn lever_is_turned(&self, actor: usize) -> bool {
+        self.actors[actor]
+            .iid
+            .as_ref()
+            .is_some_and(|iid| self.progress.turned_levers.contains(iid))
+    }
+
+    pub fn save_progress(&mut self, slot: usize) -> Result<(), StorageError> {
+        self.update_play_time();
+        self.progress.location = Location {
+            level_id: self.level.identifier.clone(),
+            x: self.actors[0].grid_x,
+            y: self.actors[0].grid_y,
+            dir: self.actors[0].face_dir,
+        };
+        let summary = SaveSlotSummary {
+            level: self.progress.level,
+            location: self.level.location_name().to_string(),
+            play_time: self.progress.stats.play_time,
+        };
+        self.progress.save(slot, &summary)?;
+        self.confirm_save_overwrite[slot] = false;
+        Ok(())
+    }
+
+    pub fn start_play_time(&mut self) {
+        self.play_time_start = miniquad::date::now();
     }
 
-    pub fn toggle_lever(&mut self) {
+    pub fn toggle_lever(&mut self, actor: usize) {
         let gctx = get_gctx();
 
-        if self.lever_is_turned() {
-            let turned_lever_pos = self
-                .progress
-                .turned_levers
-                .iter()
-                .position(|l| l == self.level.identifier.as_str())
-                .expect("turned lever position");
+        let iid = self.actors[actor].iid.clone().expect("lever iid");
+        if let Some(turned_lever_pos) = self.progress.turned_levers.iter().position(|l| *l == iid) {
             self.progress.turned_levers.swap_remove(turned_lever_pos);
         } else {
-            self.progress
-                .turned_levers
-                .push(self.level.identifier.clone());
+            self.progress.turned_levers.push(iid);
         }
 
-        sync_level_and_actors_with_lever(
+        sync_level_and_actors_with_levers(
             gctx,
-            self.lever_is_turned(),
+            &self.progress.turned_levers,
             &mut self.level,
             &mut self.actors[..],
         );
     }
 
+    // Move whole seconds played since the last call into the play time stat.
+    pub fn update_play_time(&mut self) {
+        let elapsed = (miniquad::date::now() - self.play_time_start).max(0.0) as u32;
+        self.progress.stats.play_time = self.progress.stats.play_time.saturating_add(elapsed);
+        self.play_time_start += f64::from(elapsed);
+    }
+
     pub fn place_gates(&mut self, tile_x: i32, tile_y: i32) {
         let gctx = get_gctx();
 
@@ -204,17 +241,28 @@ impl ScriptContext {
         self.modes.pop();
     }
 
-    pub fn push_battle_mode(&mut self, enemy: Enemy, boss_fight: bool) {
+    pub fn push_battle_mode(&mut self, enemies: Vec<Enemy>, boss_fight: bool) {
+        let recorder = BattleRecorder::new(
+            &self.rng,
+            &self.progress,
+            &enemies,
+            boss_fight,
+            self.battle_speed,
+        );
+        self.push_recorded_battle_mode(enemies, boss_fight, recorder);
+    }
+
+    // The replay's Progress, Rng and BattleSpeed must already be in place.
+    pub fn push_battle_replay_mode(&mut self, enemies: Vec<Enemy>, replay: BattleReplay) {
+        let boss_fight = replay.boss_fight;
+        self.push_recorded_battle_mode(enemies, boss_fight, BattleRecorder::replaying(replay));
+    }
+
+    pub fn push_bestiary_mode(&mut self) {
         let gctx = get_gctx();
 
-        self.modes.push(Battle::new(
-            gctx,
-            &self.res,
-            self.progress.max_hp,
-            self.progress.max_mp,
-            enemy,
-            boss_fight,
-        ));
+        self.modes
+            .push(Bestiary::new(gctx, &self.res, &self.progress));
     }
 
     pub fn push_credits_mode(&mut self) {
@@ -255,6 +303,42 @@ impl ScriptContext {
             .push(Options::new(gctx, &self.res, base_x, base_y, preview_music));
     }
 
+    fn push_recorded_battle_mode(
+        &mut self,
+        enemies: Vec<Enemy>,
+        boss_fight: bool,
+        recorder: BattleRecorder,
+    ) {
+        let gctx = get_gctx();
+
+        // Bosses bring their own backdrops, so they look the same when fought from the debug menu.
+        let backdrop = enemies
+            .iter()
+            .find_map(|enemy| enemy.backdrop)
+            .filter(|_| boss_fight)
+            .or(self.level.backdrop);
+        let recorder = SharedMut::new(recorder);
+        // SAFETY: The battle shares this with `record_battle`, which polls the battle.
+        self.battle_recorder = Some(unsafe { SharedMut::clone(&recorder) });
+        self.modes.push(Battle::new(
+            gctx,
+            &self.res,
+            self.progress.max_hp,
+            self.progress.max_mp,
+            enemies,
+            boss_fight,
+            backdrop,
+            recorder,
+        ));
+    }
+
+    pub fn push_slot_picker_mode(&mut self, title: &str, allow_empty: bool) {
+        let gctx = get_gctx();
+
+        self.modes
+            .push(SlotPicker::new(gctx, &self.res, title, allow_empty));
+    }
+
     pub fn push_text_box_mode(&mut self, s: &str) {
         let gctx = get_gctx();
 
@@ -291,27 +375,38 @@ impl ScriptContext {
     }
 
     update_mode!(update_battle_mode, BattleEvent);
+    update_mode!(update_bestiary_mode, BestiaryEvent);
     update_mode!(update_credits_mode, CreditsEvent);
     update_mode!(update_debug_menu_mode, DebugMenuEvent);
     update_mode!(update_ending_mode, EndingEvent);
     update_mode!(update_intro_mode, IntroEvent);
     update_mode!(update_main_menu_mode, MainMenuEvent);
     update_mode!(update_options_mode, OptionsEvent);
+    update_mode!(update_slot_picker_mode, SlotPickerEvent);
     update_mode!(update_text_box_mode, TextBoxEvent);
     update_mode!(update_title_mode, TitleEvent);
     update_mode!(update_walk_around_mode, WalkAroundEvent);
     update_mode!(update_yes_no_prompt_mode, YesNoPromptEvent);
 }
 
-fn sync_level_and_actors_with_lever(
+fn sync_level_and_actors_with_levers(
     gctx: &mut GlContext,
-    lever_turned: bool,
+    turned_levers: &[String],
     level: &mut Level,
     actors: &mut [Actor],
 ) {
-    if let Some(lever) = actors.iter_mut().find(|a| a.identifier == ActorType::Lever) {
+    let mut levers = Vec::new();
+    for lever in actors
+        .iter_mut()
+        .filter(|a| a.identifier == ActorType::Lever)
+    {
+        let lever_turned = lever
+            .iid
+            .as_ref()
+            .is_some_and(|iid| turned_levers.contains(iid));
         lever.start_animation(if lever_turned { "right" } else { "left" });
+        levers.push((lever.grid_x, lever.grid_y, lever_turned));
     }
 
-    level.sync_props_with_lever(gctx, lever_turned);
+    level.sync_props_with_levers(gctx, &levers);
 }
@@ -1,5 +1,25 @@
+use crate::backdrop::*;
 use crate::progress::*;
 use crate::random::*;
+use crate::storage::*;
+
+use miniserde::Deserialize;
+use std::collections::BTreeMap;
+
+// Debug builds load enemies from this file in the working directory instead, if it exists.
+const OVERRIDE_PATH: &str = "enemies.json";
+
+pub const MAX_FORMATION_SIZE: usize = 3;
+
+// How an enemy takes magic of an element.
+#[derive(Clone, Copy, Deserialize, Eq, PartialEq)]
+pub enum Affinity {
+    Weak,
+    Normal,
+    Resist,
+    Immune,
+    Absorb,
+}
 
 #[derive(Clone, Copy)]
 pub enum EncounterGroup {
@@ -13,656 +33,323 @@ pub enum EncounterGroup {
 
 #[derive(Clone)]
 pub struct Enemy {
-    pub name: &'static str,
-    pub sprite_path: &'static str,
+    pub name: String,
+    pub sprite_path: String,
+    // Bosses are fought in front of this instead of the level's backdrop.
+    pub backdrop: Option<Backdrop>,
+    pub level: i32,
     pub hp: i32,
     pub attack: i32,
     pub defense: i32,
-    pub weakness: Option<Magic>,
+    pub accuracy: i32,
+    pub crit_chance: i32,
+    pub evasion: i32,
+    // Elements that aren't listed here are Normal.
+    pub affinities: Vec<(Magic, Affinity)>,
     pub exp: i32,
-    pub actions: &'static [EnemyAction],
+    pub drops: Vec<ItemDrop>,
+    // Given for certain on defeat, e.g. by bosses.
+    pub reward: Option<ItemDrop>,
+    pub actions: Vec<EnemyAction>,
+    pub status_immunities: Vec<Status>,
+    pub phases: Vec<EnemyPhase>,
 }
 
-#[derive(Clone)]
+// Conditions that must all hold for an enemy action to be considered at all.
+#[derive(Clone, Deserialize)]
+pub struct ActionCondition {
+    // Percent of max HP that the enemy's HP must be below.
+    pub hp_below: Option<i32>,
+    // Only on every nth turn of the enemy, e.g. 3 for its 3rd, 6th, 9th... turns.
+    pub every_turns: Option<u32>,
+    // Whether Coric must have (true) or not have (false) follow-up magic active.
+    pub follow_up: Option<bool>,
+}
+
+#[derive(Clone, Deserialize)]
 pub struct EnemyAction {
     pub chance: u32,
-    pub msg: &'static str,
+    pub msg: String,
     pub damage_factor: Option<f32>,
+    pub inflict: Option<Infliction>,
+    pub when: Option<ActionCondition>,
+}
+
+// Once an enemy's HP drops below the threshold, it announces the phase change and swaps to the
+// phase's actions for the rest of the battle.
+#[derive(Clone, Deserialize)]
+pub struct EnemyPhase {
+    // Percent of max HP.
+    pub hp_below: i32,
+    pub msg: String,
+    pub animation: Option<String>,
+    pub actions: Vec<EnemyAction>,
+}
+
+#[derive(Clone, Copy)]
+pub struct ItemDrop {
+    pub item: Item,
+    // Percent chance of dropping; always 100 for rewards.
+    pub chance: u32,
+    pub amount: i32,
 }
 
-const ENEMIES_WILDERNESS1: &[Enemy] = &[
-    // level 1
-    Enemy {
-        name: "Rat",
-        sprite_path: "rat.png",
-        hp: 32,
-        attack: 4,
-        defense: 1,
-        weakness: None,
-        exp: 10,
-        actions: &[EnemyAction {
-            chance: 25,
-            msg: "twitches its whiskers.",
-            damage_factor: None,
-        }],
-    },
-    // level 2
-    Enemy {
-        name: "Dog",
-        sprite_path: "dog.png",
-        hp: 42,
-        attack: 6,
-        defense: 3,
-        weakness: None,
-        exp: 15,
-        actions: &[EnemyAction {
-            chance: 20,
-            msg: "growls at Coric.",
-            damage_factor: None,
-        }],
-    },
-    // level 3
-    Enemy {
-        name: "Horn Beast",
-        sprite_path: "horn-beast.png",
-        hp: 51,
-        attack: 9,
-        defense: 6,
-        weakness: None,
-        exp: 22,
-        actions: &[
-            EnemyAction {
-                chance: 15,
-                msg: "charges at Coric!",
-                damage_factor: Some(1.3),
-            },
-            EnemyAction {
-                chance: 15,
-                msg: "charges at Coric!\nCoric steps aside!",
-                damage_factor: None,
-            },
-        ],
-    },
-    // level 4
-    Enemy {
-        name: "Cobra",
-        sprite_path: "cobra.png",
-        hp: 60,
-        attack: 12,
-        defense: 8,
-        weakness: None,
-        exp: 30,
-        actions: &[EnemyAction {
-            chance: 20,
-            msg: "hisses at Coric.",
-            damage_factor: None,
-        }],
-    },
-];
+#[derive(Clone, Copy, Deserialize)]
+pub struct Infliction {
+    pub status: Status,
+    pub turns: u32,
+}
 
-const ENEMIES_WILDERNESS2: &[Enemy] = &[
-    // level 9
-    Enemy {
-        name: "Dragonfly",
-        sprite_path: "dragonfly.png",
-        hp: 107,
-        attack: 24,
-        defense: 21,
-        weakness: Some(Magic::FireEdge),
-        exp: 96,
-        actions: &[
-            EnemyAction {
-                chance: 10,
-                msg: "swoops forward and bites!",
-                damage_factor: Some(1.5),
-            },
-            EnemyAction {
-                chance: 20,
-                msg: "hovers to and fro.",
-                damage_factor: None,
-            },
-        ],
-    },
-    // level 10
-    Enemy {
-        name: "Leech",
-        sprite_path: "leech.png",
-        hp: 116,
-        attack: 27,
-        defense: 23,
-        weakness: None,
-        exp: 115,
-        actions: &[EnemyAction {
-            chance: 30,
-            msg: "bites Coric!",
-            damage_factor: Some(1.3),
-        }],
-    },
-    // level 11
-    Enemy {
-        name: "Shambler",
-        sprite_path: "shambler.png",
-        hp: 126,
-        attack: 29,
-        defense: 26,
-        weakness: None,
-        exp: 139,
-        actions: &[EnemyAction {
-            chance: 20,
-            msg: "spits a stinger at Coric!",
-            damage_factor: Some(1.5),
-        }],
-    },
-    // level 12
-    Enemy {
-        name: "Fang Frog",
-        sprite_path: "fang-frog.png",
-        hp: 135,
-        attack: 32,
-        defense: 28,
-        weakness: None,
-        exp: 166,
-        actions: &[
-            EnemyAction {
-                chance: 20,
-                msg: "lunges and bites Coric!",
-                damage_factor: Some(1.5),
-            },
-            EnemyAction {
-                chance: 10,
-                msg: "lunges at Coric!\nCoric narrowly dodges!",
-                damage_factor: None,
-            },
-        ],
-    },
-];
+#[derive(Clone, Copy, Deserialize, Eq, PartialEq)]
+pub enum Status {
+    Poison,
+    Sleep,
+    Silence,
+    Weaken,
+}
 
-const ENEMIES_WILDERNESS3: &[Enemy] = &[
-    // level 17
-    Enemy {
-        name: "Griffon",
-        sprite_path: "griffon.png",
-        hp: 182,
-        attack: 44,
-        defense: 41,
-        weakness: Some(Magic::EarthEdge),
-        exp: 414,
-        actions: &[EnemyAction {
-            chance: 30,
-            msg: "swoops with its claws bared!",
-            damage_factor: Some(1.3),
-        }],
-    },
-    // level 18
-    Enemy {
-        name: "Orc",
-        sprite_path: "orc.png",
-        hp: 191,
-        attack: 47,
-        defense: 43,
-        weakness: None,
-        exp: 496,
-        actions: &[
-            EnemyAction {
-                chance: 15,
-                msg: "stabs Coric with its dagger!",
-                damage_factor: Some(1.5),
-            },
-            EnemyAction {
-                chance: 15,
-                msg: "thrusts its dagger!\nCoric deflects the attack!",
-                damage_factor: None,
-            },
-        ],
-    },
-    // level 19
-    Enemy {
-        name: "Troll",
-        sprite_path: "troll.png",
-        hp: 200,
-        attack: 49,
-        defense: 46,
-        weakness: Some(Magic::WaterEdge),
-        exp: 596,
-        actions: &[
-            EnemyAction {
-                chance: 20,
-                msg: "swipes Coric with its claws!",
-                damage_factor: Some(1.3),
-            },
-            EnemyAction {
-                chance: 20,
-                msg: "swings its claws wildly!\nCoric blocks some strikes.",
-                damage_factor: Some(0.5),
-            },
-        ],
-    },
-    // level 20
-    Enemy {
-        name: "War Tusk",
-        sprite_path: "war-tusk.png",
-        hp: 210,
-        attack: 51,
-        defense: 49,
-        weakness: None,
-        exp: 715,
-        actions: &[
-            EnemyAction {
-                chance: 10,
-                msg: "charges and gouges Coric!",
-                damage_factor: Some(1.8),
-            },
-            EnemyAction {
-                chance: 20,
-                msg: "charges at Coric!\nCoric leaps aside!",
-                damage_factor: None,
-            },
-        ],
-    },
-];
+pub struct EnemySet {
+    enemies: Vec<Enemy>,
+    // Formations of each encounter group, as indexes into `enemies`.
+    groups: Vec<Vec<Vec<usize>>>,
+}
 
-const ENEMIES_EARTH_CASTLE: &[Enemy] = &[
-    // level 5
-    Enemy {
-        name: "Bat",
-        sprite_path: "bat.png",
-        hp: 70,
-        attack: 14,
-        defense: 11,
-        weakness: None,
-        exp: 41,
-        actions: &[
-            EnemyAction {
-                chance: 15,
-                msg: "bites Coric!",
-                damage_factor: Some(1.3),
-            },
-            EnemyAction {
-                chance: 15,
-                msg: "screeches and flutters about.",
-                damage_factor: None,
-            },
-        ],
-    },
-    // level 6
-    Enemy {
-        name: "Scorpion",
-        sprite_path: "scorpion.png",
-        hp: 79,
-        attack: 16,
-        defense: 14,
-        weakness: None,
-        exp: 53,
-        actions: &[EnemyAction {
-            chance: 25,
-            msg: "thrusts its stinger!",
-            damage_factor: Some(1.4),
-        }],
-    },
-    // level 7
-    Enemy {
-        name: "Rogue",
-        sprite_path: "rogue.png",
-        hp: 88,
-        attack: 19,
-        defense: 16,
-        weakness: None,
-        exp: 67,
-        actions: &[
-            EnemyAction {
-                chance: 20,
-                msg: "throws a knife at Coric!",
-                damage_factor: Some(1.3),
-            },
-            EnemyAction {
-                chance: 10,
-                msg: "tosses a knife!\nIt barely grazes Coric!",
-                damage_factor: Some(0.3),
-            },
-        ],
-    },
-    // level 8
-    Enemy {
-        name: "Golem",
-        sprite_path: "golem.png",
-        hp: 98,
-        attack: 22,
-        defense: 19,
-        weakness: Some(Magic::FireEdge),
-        exp: 80,
-        actions: &[EnemyAction {
-            chance: 20,
-            msg: "swings its stony fist!",
-            damage_factor: Some(1.4),
-        }],
-    },
-];
+#[derive(Deserialize)]
+struct EnemyJson {
+    name: String,
+    sprite_path: String,
+    backdrop: Option<String>,
+    level: i32,
+    hp: i32,
+    attack: i32,
+    defense: i32,
+    accuracy: Option<i32>,
+    crit_chance: Option<i32>,
+    evasion: Option<i32>,
+    affinities: Option<BTreeMap<String, Affinity>>,
+    exp: i32,
+    drops: Option<Vec<ItemDropJson>>,
+    reward: Option<ItemDropJson>,
+    actions: Vec<EnemyAction>,
+    status_immunities: Option<Vec<Status>>,
+    phases: Option<Vec<EnemyPhase>>,
+}
 
-const ENEMIES_WATER_CASTLE: &[Enemy] = &[
-    // level 13
-    Enemy {
-        name: "Jelly",
-        sprite_path: "jelly.png",
-        hp: 144,
-        attack: 34,
-        defense: 31,
-        weakness: None,
-        exp: 199,
-        actions: &[EnemyAction {
-            chance: 15,
-            msg: "quivers in place.",
-            damage_factor: None,
-        }],
-    },
-    // level 14
-    Enemy {
-        name: "Ghost",
-        sprite_path: "ghost.png",
-        hp: 154,
-        attack: 37,
-        defense: 33,
-        weakness: None,
-        exp: 239,
-        actions: &[
-            EnemyAction {
-                chance: 20,
-                msg: "extends its ethereal touch!",
-                damage_factor: Some(1.3),
-            },
-            EnemyAction {
-                chance: 20,
-                msg: "emits a chilling breeze!",
-                damage_factor: Some(0.7),
-            },
-        ],
-    },
-    // level 15
-    Enemy {
-        name: "Turtle",
-        sprite_path: "turtle.png",
-        hp: 163,
-        attack: 39,
-        defense: 66,
-        weakness: None,
-        exp: 287,
-        actions: &[
-            EnemyAction {
-                chance: 20,
-                msg: "extends its neck and bites!",
-                damage_factor: Some(1.5),
-            },
-            EnemyAction {
-                chance: 10,
-                msg: "slowly advances on Coric.",
-                damage_factor: None,
-            },
-        ],
-    },
-    // level 16
-    Enemy {
-        name: "Serpent",
-        sprite_path: "serpent.png",
-        hp: 172,
-        attack: 42,
-        defense: 39,
-        weakness: Some(Magic::EarthEdge),
-        exp: 345,
-        actions: &[
-            EnemyAction {
-                chance: 15,
-                msg: "fires a jet of water!",
-                damage_factor: Some(1.7),
-            },
-            EnemyAction {
-                chance: 15,
-                msg: "fires a jet of water!\nCoric dodges some of it!",
-                damage_factor: Some(0.5),
-            },
-            EnemyAction {
-                chance: 15,
-                msg: "roars in anger!",
-                damage_factor: None,
-            },
-        ],
-    },
-];
+#[derive(Deserialize)]
+struct ItemDropJson {
+    item: String,
+    chance: Option<u32>,
+    amount: Option<i32>,
+}
 
-const ENEMIES_FIRE_CASTLE: &[Enemy] = &[
-    // level 21
-    Enemy {
-        name: "Basilisk",
-        sprite_path: "basilisk.png",
-        hp: 219,
-        attack: 54,
-        defense: 51,
-        weakness: None,
-        exp: 858,
-        actions: &[
-            EnemyAction {
-                chance: 15,
-                msg: "casts its burning gaze!",
-                damage_factor: Some(1.4),
-            },
-            EnemyAction {
-                chance: 15,
-                msg: "casts its burning gaze!\nCoric narrowly averts his eyes!",
-                damage_factor: None,
-            },
-        ],
-    },
-    // level 22
-    Enemy {
-        name: "Warlock",
-        sprite_path: "warlock.png",
-        hp: 228,
-        attack: 57,
-        defense: 53,
-        weakness: None,
-        exp: 1029,
-        actions: &[
-            EnemyAction {
-                chance: 20,
-                msg: "conjures infernal bolts!",
-                damage_factor: Some(1.5),
-            },
-            EnemyAction {
-                chance: 10,
-                msg: "mutters incoherent curses.",
-                damage_factor: None,
-            },
-        ],
-    },
-    // level 23
-    Enemy {
-        name: "Minotaur",
-        sprite_path: "minotaur.png",
-        hp: 238,
-        attack: 59,
-        defense: 56,
-        weakness: None,
-        exp: 1235,
-        actions: &[
-            EnemyAction {
-                chance: 15,
-                msg: "swings its huge axe!",
-                damage_factor: Some(1.5),
-            },
-            EnemyAction {
-                chance: 15,
-                msg: "swings its huge axe!\nCoric blocks the strike!",
-                damage_factor: None,
-            },
-            EnemyAction {
-                chance: 10,
-                msg: "grunts in anger.",
-                damage_factor: None,
-            },
-        ],
-    },
-    // level 24
-    Enemy {
-        name: "Vampire",
-        sprite_path: "vampire.png",
-        hp: 247,
-        attack: 62,
-        defense: 59,
-        weakness: Some(Magic::WaterEdge),
-        exp: 1482,
-        actions: &[
-            EnemyAction {
-                chance: 25,
-                msg: "sinks its fangs into Coric!",
-                damage_factor: Some(1.7),
-            },
-            EnemyAction {
-                chance: 10,
-                msg: "lunges at Coric!\nCoric barely dodges!",
-                damage_factor: None,
-            },
-            EnemyAction {
-                chance: 10,
-                msg: "bares its fangs.",
-                damage_factor: None,
-            },
-        ],
-    },
-];
+#[derive(Deserialize)]
+struct EnemySetJson {
+    enemies: Vec<EnemyJson>,
+    groups: BTreeMap<String, Vec<Vec<String>>>,
+}
 
-impl Enemy {
-    pub fn earth_spirit() -> Self {
-        Self {
-            name: "Earth",
-            sprite_path: "earth.png",
-            hp: 1700,
-            attack: 27,
-            defense: 24,
-            weakness: Some(Magic::FireEdge),
-            exp: 500,
-            actions: &[
-                EnemyAction {
-                    chance: 10,
-                    msg: "hurls a massive boulder!",
-                    damage_factor: Some(2.0),
-                },
-                EnemyAction {
-                    chance: 10,
-                    msg: "hurls a massive boulder!\nCoric deftly leaps aside!",
-                    damage_factor: None,
-                },
-                EnemyAction {
-                    chance: 10,
-                    msg: "slams its fist the ground!\nCoric is pummeled by debris!",
-                    damage_factor: Some(1.5),
-                },
-                EnemyAction {
-                    chance: 10,
-                    msg: "throws debris at Coric!\nCoric deflects some of it!",
-                    damage_factor: Some(0.5),
-                },
-                EnemyAction {
-                    chance: 10,
-                    msg: "roars with a bitter rage!",
-                    damage_factor: None,
-                },
-            ],
+impl Affinity {
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Weak => "Weak",
+            Self::Normal => "Normal",
+            Self::Resist => "Resist",
+            Self::Immune => "Immune",
+            Self::Absorb => "Absorb",
         }
     }
+}
 
-    pub fn fire_spirit() -> Self {
-        Self {
-            name: "Fire",
-            sprite_path: "fire.png",
-            hp: 10000,
-            attack: 77,
-            defense: 74,
-            weakness: Some(Magic::WaterEdge),
-            exp: 0,
-            actions: &[
-                EnemyAction {
-                    chance: 10,
-                    msg: "summons roaring flames!\nCoric is roasted!",
-                    damage_factor: Some(1.8),
-                },
-                EnemyAction {
-                    chance: 10,
-                    msg: "shoots infernal bolts!\nOne of them hits Coric!",
-                    damage_factor: Some(1.5),
-                },
-                EnemyAction {
-                    chance: 10,
-                    msg: "shoots infernal bolts!\nCoric weaves between them!",
-                    damage_factor: None,
-                },
-                EnemyAction {
-                    chance: 10,
-                    msg: "whips up glowing embers!\nCoric is burned!",
-                    damage_factor: Some(0.7),
-                },
-                EnemyAction {
-                    chance: 10,
-                    msg: "lets out a piercing cry!",
-                    damage_factor: None,
-                },
-            ],
-        }
+impl Enemy {
+    pub fn affinity(&self, magic: Magic) -> Affinity {
+        self.affinities
+            .iter()
+            .find(|(m, _)| *m == magic)
+            .map_or(Affinity::Normal, |&(_, affinity)| affinity)
     }
+}
 
-    pub fn water_spirit() -> Self {
-        Self {
-            name: "Water",
-            sprite_path: "water.png",
-            hp: 5000,
-            attack: 49,
-            defense: 46,
-            weakness: Some(Magic::EarthEdge),
-            exp: 2000,
-            actions: &[
-                EnemyAction {
-                    chance: 10,
-                    msg: "throws columns of ice!\nOne of them hits Coric!",
-                    damage_factor: Some(1.6),
-                },
-                EnemyAction {
-                    chance: 10,
-                    msg: "throws columns of ice!\nCoric narrowly dodges them!",
-                    damage_factor: None,
-                },
-                EnemyAction {
-                    chance: 10,
-                    msg: "summons a huge wave!\nCoric is slammed!",
-                    damage_factor: Some(1.3),
-                },
-                EnemyAction {
-                    chance: 10,
-                    msg: "fires a torrent of water!",
-                    damage_factor: Some(1.1),
-                },
-                EnemyAction {
-                    chance: 10,
-                    msg: "emits a hollow wail!",
-                    damage_factor: None,
-                },
-            ],
+impl EnemySet {
+    pub fn new() -> Self {
+        let override_data = if cfg!(debug_assertions) {
+            std::fs::read_to_string(OVERRIDE_PATH).ok()
+        } else {
+            None
+        };
+        let (source, data) = match &override_data {
+            Some(data) => (OVERRIDE_PATH, data.as_str()),
+            None => (
+                "assets/enemies.json",
+                include_str!("../assets/enemies.json"),
+            ),
+        };
+        let json: EnemySetJson = from_json(data).unwrap_or_else(|e| panic!("{source}: {e}"));
+
+        let mut enemies: Vec<Enemy> = Vec::with_capacity(json.enemies.len());
+        for e in json.enemies {
+            if enemies.iter().any(|enemy| enemy.name == e.name) {
+                panic!("{source}: duplicate enemy: {}", e.name);
+            }
+            if e.level < 1 {
+                panic!("{source}: {}: level must be at least 1", e.name);
+            }
+            if e.hp < 1 {
+                panic!("{source}: {}: hp must be at least 1", e.name);
+            }
+            for (field, value) in [("attack", e.attack), ("defense", e.defense), ("exp", e.exp)] {
+                if value < 0 {
+                    panic!("{source}: {}: {field} must not be negative", e.name);
+                }
+            }
+            let backdrop = e.backdrop.as_deref().map(|name| {
+                Backdrop::from_name(name)
+                    .unwrap_or_else(|| panic!("{source}: {}: unknown backdrop: {name}", e.name))
+            });
+            check_actions(source, &e.name, &e.actions);
+            let phases = e.phases.unwrap_or_default();
+            let mut prev_hp_below = 101;
+            for phase in &phases {
+                if !(1..prev_hp_below).contains(&phase.hp_below) {
+                    panic!(
+                        "{source}: {}: phase hp_below must be from 1 to 100 and decreasing",
+                        e.name
+                    );
+                }
+                prev_hp_below = phase.hp_below;
+                check_actions(source, &e.name, &phase.actions);
+            }
+            let affinities = e
+                .affinities
+                .unwrap_or_default()
+                .into_iter()
+                .map(|(magic, affinity)| match magic.as_str() {
+                    "EarthEdge" => (Magic::EarthEdge, affinity),
+                    "WaterEdge" => (Magic::WaterEdge, affinity),
+                    "FireEdge" => (Magic::FireEdge, affinity),
+                    _ => panic!("{source}: {}: unknown affinity element: {magic}", e.name),
+                })
+                .collect();
+            let to_item_drop = |drop: ItemDropJson, default_chance: Option<u32>| {
+                let Some(item) = Item::ALL.into_iter().find(|i| i.name() == drop.item) else {
+                    panic!("{source}: {}: unknown item: {}", e.name, drop.item);
+                };
+                let Some(chance) = drop.chance.or(default_chance) else {
+                    panic!("{source}: {}: drop of {} needs a chance", e.name, drop.item);
+                };
+                let amount = drop.amount.unwrap_or(1);
+                if !(1..=100).contains(&chance) || !(1..=MAX_ITEM_AMOUNT).contains(&amount) {
+                    panic!(
+                        "{source}: {}: drop of {} must have a chance from 1 to 100 and an \
+                         amount from 1 to {MAX_ITEM_AMOUNT}",
+                        e.name, drop.item
+                    );
+                }
+                ItemDrop {
+                    item,
+                    chance,
+                    amount,
+                }
+            };
+            let drops = e
+                .drops
+                .unwrap_or_default()
+                .into_iter()
+                .map(|drop| to_item_drop(drop, None))
+                .collect();
+            if e.reward.as_ref().is_some_and(|r| r.chance.is_some()) {
+                panic!(
+                    "{source}: {}: rewards are always given, so can't have a chance",
+                    e.name
+                );
+            }
+            let reward = e.reward.map(|drop| to_item_drop(drop, Some(100)));
+            enemies.push(Enemy {
+                name: e.name,
+                sprite_path: e.sprite_path,
+                backdrop,
+                level: e.level,
+                hp: e.hp,
+                attack: e.attack,
+                defense: e.defense,
+                // Enemies have the same chances as Coric without equipment unless they say otherwise.
+                accuracy: e.accuracy.unwrap_or(BASE_ACCURACY),
+                crit_chance: e.crit_chance.unwrap_or(BASE_CRIT_CHANCE),
+                evasion: e.evasion.unwrap_or(BASE_EVASION),
+                affinities,
+                exp: e.exp,
+                drops,
+                reward,
+                actions: e.actions,
+                status_immunities: e.status_immunities.unwrap_or_default(),
+                phases,
+            });
+        }
+
+        let mut groups = vec![Vec::new(); EncounterGroup::NUM_GROUPS];
+        for (group_name, formations) in &json.groups {
+            let group = &mut groups[EncounterGroup::from(group_name.as_str()) as usize];
+            for members in formations {
+                if !(1..=MAX_FORMATION_SIZE).contains(&members.len()) {
+                    panic!("{source}: {group_name}: formations must have 1 to {MAX_FORMATION_SIZE} enemies");
+                }
+                let formation = members
+                    .iter()
+                    .map(|member| {
+                        enemies
+                            .iter()
+                            .position(|enemy| &enemy.name == member)
+                            .unwrap_or_else(|| {
+                                panic!("{source}: {group_name}: unknown enemy: {member}")
+                            })
+                    })
+                    .collect();
+                group.push(formation);
+            }
         }
+        if let Some(i) = groups.iter().position(Vec::is_empty) {
+            panic!("{source}: encounter group {i} has no formations");
+        }
+
+        Self { enemies, groups }
+    }
+
+    // Every kind of enemy, in roughly the order that they're first met.
+    pub fn all(&self) -> &[Enemy] {
+        &self.enemies
+    }
+
+    pub fn by_name(&self, name: &str) -> Enemy {
+        self.enemies
+            .iter()
+            .find(|enemy| enemy.name == name)
+            .unwrap_or_else(|| panic!("unknown enemy: {name}"))
+            .clone()
+    }
+
+    pub fn random_formation(&self, group: EncounterGroup, rng: &mut Rng) -> Vec<Enemy> {
+        let group = &self.groups[group as usize];
+        let i = u32::try_from(group.len()).expect("u32 enemy group length");
+        group[rng.random(i) as usize]
+            .iter()
+            .map(|&i| self.enemies[i].clone())
+            .collect()
+    }
+}
+
+impl ActionCondition {
+    pub fn holds(&self, hp_percent: i32, turn: u32, follow_up: bool) -> bool {
+        self.hp_below.map_or(true, |hp_below| hp_percent < hp_below)
+            && self.every_turns.map_or(true, |n| turn % n == 0)
+            && self.follow_up.map_or(true, |f| f == follow_up)
     }
 }
 
 impl EncounterGroup {
     pub const NUM_GROUPS: usize = Self::FireCastle as usize + 1;
+}
 
-    pub fn random_enemy(self, rng: &mut Rng) -> Enemy {
-        let group = match self {
-            Self::Wilderness1 => ENEMIES_WILDERNESS1,
-            Self::Wilderness2 => ENEMIES_WILDERNESS2,
-            Self::Wilderness3 => ENEMIES_WILDERNESS3,
-            Self::EarthCastle => ENEMIES_EARTH_CASTLE,
-            Self::WaterCastle => ENEMIES_WATER_CASTLE,
-            Self::FireCastle => ENEMIES_FIRE_CASTLE,
-        };
-        assert!(!group.is_empty());
-        let i = u32::try_from(group.len()).expect("u32 enemy group length");
-        group[rng.random(i) as usize].clone()
-    }
+impl Status {
+    pub const ALL: [Self; 4] = [Self::Poison, Self::Sleep, Self::Silence, Self::Weaken];
 }
 
 impl From<&str> for EncounterGroup {
@@ -678,3 +365,28 @@ impl From<&str> for EncounterGroup {
         }
     }
 }
+
+// Conditional actions can add up to more than 100, since they only apply some of the time, and
+// take precedence over the actions listed after them when they do.
+fn check_actions(source: &str, name: &str, actions: &[EnemyAction]) {
+    let unconditional_chances: u32 = actions
+        .iter()
+        .filter(|a| a.when.is_none())
+        .map(|a| a.chance)
+        .sum();
+    if unconditional_chances > 100 {
+        panic!("{source}: {name}: action chances add up to over 100");
+    }
+    if actions
+        .iter()
+        .any(|a| a.inflict.is_some_and(|i| i.turns == 0))
+    {
+        panic!("{source}: {name}: statuses must be inflicted for 1+ turns");
+    }
+    if actions
+        .iter()
+        .any(|a| a.when.as_ref().is_some_and(|w| w.every_turns == Some(0)))
+    {
+        panic!("{source}: {name}: every_turns must be 1+");
+    }
+}
@@ -68,6 +68,15 @@ impl Input {
         self.keys_pressed[game_key as usize]
     }
 
+    // Keys pressed this frame, one bit per `GameKey`.
+    pub fn pressed_bits(&self) -> u8 {
+        self.keys_pressed
+            .iter()
+            .enumerate()
+            .filter(|(_, &pressed)| pressed)
+            .fold(0, |bits, (i, _)| bits | 1 << i)
+    }
+
     pub fn reset_keys_down(&mut self) {
         self.keys_down.fill(false);
     }
@@ -75,4 +84,10 @@ impl Input {
     pub fn reset_keys_pressed(&mut self) {
         self.keys_pressed.fill(false);
     }
+
+    pub fn set_pressed_bits(&mut self, bits: u8) {
+        for (i, pressed) in self.keys_pressed.iter_mut().enumerate() {
+            *pressed = bits & 1 << i != 0;
+        }
+    }
 }
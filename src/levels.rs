@@ -1,5 +1,6 @@
 use crate::actor::*;
 use crate::audio::*;
+use crate::backdrop::*;
 use crate::direction::*;
 use crate::enemy::*;
 use crate::resources::*;
@@ -36,6 +37,8 @@ pub struct Level {
     pub neighbours: Vec<NeighbourLevel>,
     pub encounters: Option<EncounterGroup>,
     pub music: Option<Music>,
+    // Battles fought here are drawn on a plain background without one.
+    pub backdrop: Option<Backdrop>,
 }
 
 pub struct LevelSet {
@@ -201,11 +204,25 @@ impl Layer {
         gctx.texture_update(self.bindings.images[0], &self.tile_data[..]);
     }
 
-    fn sync_props_with_lever(&mut self, gctx: &mut GlContext, lever_turned: bool) {
+    // Each door and spike tile follows the lever nearest to it, given as (tile_x, tile_y, turned).
+    //
+    // Props are tiles rather than entities, so nothing links them to a lever but distance. A
+    // level with more than one lever must keep each lever's doors and spikes closer to it than to
+    // any other lever, or the levers will control each other's props. Every level so far has at
+    // most one lever.
+    fn sync_props_with_levers(&mut self, gctx: &mut GlContext, levers: &[(i32, i32, bool)]) {
         assert!(self.identifier == "Props");
 
-        if lever_turned {
-            for tile in self.tile_data.chunks_exact_mut(4) {
+        let c_wid = self.c_wid as i32;
+        for (i, tile) in self.tile_data.chunks_exact_mut(4).enumerate() {
+            let tile_x = i as i32 % c_wid;
+            let tile_y = i as i32 / c_wid;
+            let lever_turned = levers
+                .iter()
+                .min_by_key(|(x, y, _)| (x - tile_x).pow(2) + (y - tile_y).pow(2))
+                .is_some_and(|&(_, _, turned)| turned);
+
+            if lever_turned {
                 match (tile[0], tile[1]) {
                     (1, 0) => {
                         // closed door -> open door
@@ -219,9 +236,7 @@ impl Layer {
                     }
                     _ => {}
                 }
-            }
-        } else {
-            for tile in self.tile_data.chunks_exact_mut(4) {
+            } else {
                 match (tile[0], tile[1]) {
                     (1, 1) => {
                         // open door -> closed door
@@ -291,6 +306,7 @@ impl Level {
 
         let mut encounters: Option<EncounterGroup> = None;
         let mut music: Option<Music> = None;
+        let mut backdrop: Option<Backdrop> = None;
         for field in &level_json.field_instances {
             match &field.identifier[..] {
                 "EncounterGroup" => {
@@ -307,6 +323,13 @@ impl Level {
                         v => panic!("Music must be a string or null: {:?}", v),
                     }
                 }
+                "Backdrop" => {
+                    backdrop = match &field.value {
+                        Some(json::Value::String(s)) => Some(s.as_str().into()),
+                        None | Some(json::Value::Null) => None,
+                        v => panic!("Backdrop must be a string or null: {:?}", v),
+                    }
+                }
                 id => panic!("unknown level field: {id}"),
             }
         }
@@ -326,6 +349,7 @@ impl Level {
                     .collect(),
                 encounters,
                 music,
+                backdrop,
             },
             actors,
         )
@@ -353,6 +377,18 @@ impl Level {
             .is_ice_tile(tile_x, tile_y)
     }
 
+    pub fn location_name(&self) -> &'static str {
+        match self.encounters {
+            None => "Town",
+            Some(EncounterGroup::Wilderness1) => "Eastern Plains",
+            Some(EncounterGroup::Wilderness2) => "Western Woods",
+            Some(EncounterGroup::Wilderness3) => "Northern Cliffs",
+            Some(EncounterGroup::EarthCastle) => "Earth Castle",
+            Some(EncounterGroup::WaterCastle) => "Water Castle",
+            Some(EncounterGroup::FireCastle) => "Fire Castle",
+        }
+    }
+
     pub fn place_gates(&mut self, gctx: &mut GlContext, tile_x: i32, tile_y: i32) {
         self.layers
             .iter_mut()
@@ -361,12 +397,12 @@ impl Level {
             .place_gates(gctx, tile_x, tile_y);
     }
 
-    pub fn sync_props_with_lever(&mut self, gctx: &mut GlContext, lever_turned: bool) {
+    pub fn sync_props_with_levers(&mut self, gctx: &mut GlContext, levers: &[(i32, i32, bool)]) {
         self.layers
             .iter_mut()
             .find(|l| l.identifier == "Props")
             .expect("Props layer")
-            .sync_props_with_lever(gctx, lever_turned);
+            .sync_props_with_levers(gctx, levers);
     }
 }
 
@@ -4,6 +4,9 @@ mod actor;
 mod aseprite;
 mod async_utils;
 mod audio;
+mod backdrop;
+mod backdrop_image;
+mod battle_engine;
 mod contexts;
 mod direction;
 mod enemy;
@@ -15,11 +18,15 @@ mod meter;
 mod modes;
 mod progress;
 mod random;
+mod replay;
 mod resources;
 mod saved_options;
+mod screen_shake;
 mod script;
 mod shaders;
+mod spell_effect;
 mod sprite;
+mod storage;
 mod text;
 mod window;
 
@@ -33,6 +40,7 @@ use levels::*;
 use modes::*;
 use resources::*;
 use saved_options::*;
+use screen_shake::*;
 use shaders::*;
 
 use miniquad::{
@@ -66,6 +74,7 @@ struct App {
     level: SharedMut<Level>,
     actors: SharedMut<Vec<Actor>>,
     fade: SharedMut<Fade>,
+    screen_shake: SharedMut<ScreenShake>,
 }
 
 impl App {
@@ -114,7 +123,20 @@ impl App {
             (SharedMut::new(level), SharedMut::new(actors))
         };
         let fade = SharedMut::new(Fade::new());
-        let sctx = ScriptContext::new(res, &input, &audio, &modes, &level, &actors, &fade);
+        let screen_shake = SharedMut::new(ScreenShake::new());
+        let mut sctx = ScriptContext::new(
+            res,
+            &input,
+            &audio,
+            &modes,
+            &level,
+            &actors,
+            &fade,
+            &screen_shake,
+        );
+        if let Some(battle_speed) = opts.battle_speed {
+            sctx.battle_speed = battle_speed;
+        }
 
         Self {
             script: Some(Box::pin(script::script_main(sctx))),
@@ -133,6 +155,7 @@ impl App {
             level,
             actors,
             fade,
+            screen_shake,
         }
     }
 
@@ -162,8 +185,13 @@ impl EventHandler for App {
         gctx.begin_default_pass(Default::default());
         gctx.apply_pipeline(&self.screen_pipeline);
         gctx.apply_bindings(&self.screen_bindings);
+        let [shake_x, shake_y]: [i32; 2] = (&*self.screen_shake).into();
         gctx.apply_uniforms(UniformsSource::table(&screen_shader::Uniforms {
             scale: self.window_scale(),
+            offset: [
+                shake_x as f32 * 2.0 / SCREEN_WIDTH as f32,
+                shake_y as f32 * -2.0 / SCREEN_HEIGHT as f32,
+            ],
             fade: (&*self.fade).into(),
         }));
         gctx.draw(0, 6, 1);
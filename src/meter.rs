@@ -64,6 +64,7 @@ impl Meter {
             px_dest_offset: self.offset,
             px_framebuffer_size: [SCREEN_WIDTH as f32, SCREEN_HEIGHT as f32],
             px_texture_size: [1.0, 4.0],
+            tint: [0.0; 4],
         }));
         if self.width >= 3.0 {
             gctx.draw(0, 6, 5);
@@ -1,30 +1,37 @@
-use crate::async_utils::wait_once;
+use crate::async_utils::*;
 use crate::audio::*;
+use crate::backdrop::*;
+use crate::backdrop_image::*;
+use crate::battle_engine::*;
 use crate::contexts::*;
 use crate::enemy::*;
 use crate::input::*;
 use crate::meter::*;
 use crate::progress::*;
-use crate::random::*;
+use crate::replay::*;
 use crate::resources::*;
+use crate::spell_effect::*;
 use crate::sprite::*;
 use crate::text::*;
 use crate::window::*;
+use crate::SCREEN_WIDTH;
 
 use miniquad::GlContext;
 
-const ENEMY_X: i32 = 104;
 const ENEMY_Y: i32 = 32;
-const MESSAGE_X: i32 = 18;
+// Horizontal space taken by each enemy side by side in the enemy window.
+const ENEMY_SLOT_WIDTH: i32 = 64;
+const MESSAGE_X: i32 = 14;
 const MESSAGE_Y: i32 = 116;
-const STATUS_X: i32 = 250;
+const STATUS_X: i32 = 246;
 const STATUS_Y: i32 = 116;
 
 pub struct Battle {
     enemy_window: Window,
-    enemy_sprite: Sprite,
-    enemy_hp_meter: Meter,
-    enemy_visible: bool,
+    backdrop: Option<BackdropImage>,
+    enemy_displays: Vec<EnemyDisplay>,
+    target_cursor: Text,
+    target_cursor_visible: bool,
     message_window: Window,
     message_text: Text,
     menu_text: Text,
@@ -36,12 +43,15 @@ pub struct Battle {
     mp_text: Text,
     hp_meter: Meter,
     mp_meter: Meter,
+    status_markers: Text,
+    // Drawn over everything but the change window while a spell is being cast.
+    spell_effect: Option<SpellEffect>,
     change_window: Window,
     change_text: Text,
     change_visible: bool,
-    enemy: Enemy,
-    enemy_max_hp: i32,
+    engine: BattleEngine,
     boss_fight: bool,
+    recorder: SharedMut<BattleRecorder>,
 }
 
 pub enum BattleEvent {
@@ -50,46 +60,75 @@ pub enum BattleEvent {
     Victory,
 }
 
-enum PlayerChoice {
-    Fight,
-    Magic(usize),
-    Item(usize),
-    Run,
+struct EnemyDisplay {
+    sprite: Sprite,
+    hp_meter: Meter,
+    status_markers: Text,
+    visible: bool,
+    // Left edge of the enemy's sprite.
+    x: i32,
+    // Horizontal offset of the sprite while it's being shaken.
+    shake: i32,
+    // Mixed into the sprite's colors while it's flashing.
+    tint: [f32; 4],
 }
 
 impl Battle {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         gctx: &mut GlContext,
         res: &Resources,
         max_hp: i32,
         max_mp: i32,
-        enemy: Enemy,
+        enemies: Vec<Enemy>,
         boss_fight: bool,
+        backdrop: Option<Backdrop>,
+        recorder: SharedMut<BattleRecorder>,
     ) -> Self {
-        let mut enemy_sprite = Sprite::new(gctx, res, enemy.sprite_path);
-        enemy_sprite.start_animation("idle");
-
-        let enemy_max_hp = enemy.hp;
+        let enemy_window_width = 48 + ENEMY_SLOT_WIDTH * enemies.len() as i32;
+        let enemy_window_x = (SCREEN_WIDTH as i32 - enemy_window_width) / 2;
+        let enemy_displays = enemies
+            .iter()
+            .enumerate()
+            .map(|(i, enemy)| {
+                let x = enemy_window_x + 40 + ENEMY_SLOT_WIDTH * i as i32;
+                let mut sprite = Sprite::new(gctx, res, &enemy.sprite_path);
+                sprite.start_animation("idle");
+                EnemyDisplay {
+                    sprite,
+                    hp_meter: Meter::new(gctx, res, x, ENEMY_Y + 18, 32, [0, 192, 0], enemy.hp),
+                    status_markers: Text::new(res, x + 36, ENEMY_Y + 15),
+                    visible: true,
+                    x,
+                    shake: 0,
+                    tint: [0.0; 4],
+                }
+            })
+            .collect();
 
         Self {
-            enemy_window: Window::new(gctx, res, ENEMY_X, ENEMY_Y, 112, 80),
-            enemy_sprite,
-            enemy_hp_meter: Meter::new(
-                gctx,
-                res,
-                ENEMY_X + 40,
-                ENEMY_Y + 18,
-                32,
-                [0, 192, 0],
-                enemy.hp,
-            ),
-            enemy_visible: true,
+            enemy_window: Window::new(gctx, res, enemy_window_x, ENEMY_Y, enemy_window_width, 80),
+            // Just inside the borders of the enemy window.
+            backdrop: backdrop.map(|backdrop| {
+                BackdropImage::new(
+                    gctx,
+                    res,
+                    backdrop,
+                    enemy_window_x + 4,
+                    ENEMY_Y + 4,
+                    enemy_window_width - 8,
+                    80 - 8,
+                )
+            }),
+            enemy_displays,
+            target_cursor: Text::from_str(gctx, res, 0, ENEMY_Y + 36, "►"),
+            target_cursor_visible: false,
             message_window: Window::new(gctx, res, MESSAGE_X, MESSAGE_Y, 232, 48),
             message_text: Text::new(res, MESSAGE_X + 8, MESSAGE_Y + 8),
             menu_text: Text::new(res, MESSAGE_X + 8, MESSAGE_Y + 24),
             cursor: Text::from_str(gctx, res, MESSAGE_X + 8, MESSAGE_Y + 32, "►"),
             menu_visible: false,
-            status_window: Window::new(gctx, res, STATUS_X, STATUS_Y, 52, 48),
+            status_window: Window::new(gctx, res, STATUS_X, STATUS_Y, 60, 48),
             status_visible: true,
             hp_text: Text::new(res, STATUS_X + 8, STATUS_Y + 8),
             mp_text: Text::new(res, STATUS_X + 8, STATUS_Y + 24),
@@ -111,12 +150,14 @@ impl Battle {
                 [0, 192, 192],
                 max_mp,
             ),
+            status_markers: Text::new(res, STATUS_X + 46, STATUS_Y + 8),
+            spell_effect: None,
             change_window: Window::new(gctx, res, 0, 0, 16, 24),
             change_text: Text::new(res, 0, 0),
             change_visible: false,
-            enemy,
-            enemy_max_hp,
+            engine: BattleEngine::new(enemies),
             boss_fight,
+            recorder,
         }
     }
 
@@ -124,12 +165,12 @@ impl Battle {
         &mut self,
         mctx: &mut ModeContext<'_, '_>,
         has_follow_up: bool,
-    ) -> PlayerChoice {
+    ) -> PlayerAction {
         fn update_action_cursor(cursor: &mut Text, which: usize) {
             //           1         2         3
             // 012345678901234567890123456789012345
-            // .Fight     .Magic    .Item    .Run
-            const ACTION_POSITIONS: [i32; 4] = [0, 11, 21, 30];
+            // .Fight    .Magic .Item .Defend .Run
+            const ACTION_POSITIONS: [i32; 5] = [0, 10, 17, 23, 31];
             let x = ACTION_POSITIONS[which] * 6;
             let y = 8;
             cursor.set_offset(MESSAGE_X + 8 + x, MESSAGE_Y + 24 + y);
@@ -139,23 +180,25 @@ impl Battle {
             mctx: &mut ModeContext,
             message_text: &mut Text,
             menu_text: &mut Text,
-            enemy_name: &str,
+            engine: &BattleEngine,
             has_follow_up: bool,
         ) {
+            let mut remaining = engine.enemies.iter().filter(|e| !e.defeated());
+            let enemy_name = match (remaining.next(), remaining.next()) {
+                (Some(enemy), None) => format!("{} prepares", enemy.name),
+                _ => String::from("The enemies prepare"),
+            };
             message_text.set_text(
                 mctx.gctx,
                 mctx.res,
-                &format!(
-                    "{} prepares to fight!\nChoose your course of action:",
-                    enemy_name,
-                ),
+                &format!("{enemy_name} to fight!\nChoose your course of action:"),
             );
 
             menu_text.set_text(
                 mctx.gctx,
                 mctx.res,
                 &format!(
-                    "\n {:8}   Magic     Item     Run",
+                    "\n {:8}  Magic  Item  Defend  Run",
                     if has_follow_up { "FollowUp" } else { "Fight" },
                 ),
             );
@@ -165,26 +208,43 @@ impl Battle {
             mctx,
             &mut self.message_text,
             &mut self.menu_text,
-            self.enemy.name,
+            &self.engine,
             has_follow_up,
         );
-        self.message_text.reveal().await;
+        self.reveal_message(mctx).await;
         self.menu_visible = true;
 
         let mut selection = 0;
         update_action_cursor(&mut self.cursor, selection);
 
         loop {
-            self.enemy_sprite.animate();
+            self.animate_enemies();
 
             wait_once().await;
 
             if mctx.input.is_key_pressed(GameKey::Confirm) {
                 let choice = match selection {
-                    0 => Some(PlayerChoice::Fight),
-                    1 => self.magic_menu(mctx).await.map(PlayerChoice::Magic),
-                    2 => self.item_menu(mctx).await.map(PlayerChoice::Item),
-                    3 => Some(PlayerChoice::Run).filter(|_| !self.boss_fight),
+                    0 => self.target_menu(mctx).await.map(PlayerAction::Fight),
+                    1 => match self.magic_menu(mctx).await {
+                        Some(choice) if mctx.progress.magic[choice].magic == Magic::Heal => {
+                            Some(PlayerAction::Magic(choice, 0))
+                        }
+                        Some(choice) => self
+                            .target_menu(mctx)
+                            .await
+                            .map(|target| PlayerAction::Magic(choice, target)),
+                        None => None,
+                    },
+                    2 => match self.item_menu(mctx).await {
+                        Some(choice) if mctx.progress.items[choice].item == Item::Lens => self
+                            .target_menu(mctx)
+                            .await
+                            .map(|target| PlayerAction::Item(choice, target)),
+                        Some(choice) => Some(PlayerAction::Item(choice, 0)),
+                        None => None,
+                    },
+                    3 => Some(PlayerAction::Defend),
+                    4 => Some(PlayerAction::Run).filter(|_| !self.boss_fight),
                     _ => None,
                 };
                 if let Some(choice) = choice {
@@ -196,7 +256,7 @@ impl Battle {
                         mctx,
                         &mut self.message_text,
                         &mut self.menu_text,
-                        self.enemy.name,
+                        &self.engine,
                         has_follow_up,
                     );
                     update_action_cursor(&mut self.cursor, selection);
@@ -204,14 +264,14 @@ impl Battle {
             } else if mctx.input.is_key_pressed(GameKey::Left) {
                 mctx.audio.play_sfx(Sfx::Cursor);
                 match selection {
-                    0 => selection = 3,
+                    0 => selection = 4,
                     _ => selection -= 1,
                 }
                 update_action_cursor(&mut self.cursor, selection);
             } else if mctx.input.is_key_pressed(GameKey::Right) {
                 mctx.audio.play_sfx(Sfx::Cursor);
                 match selection {
-                    3 => selection = 0,
+                    4 => selection = 0,
                     _ => selection += 1,
                 }
                 update_action_cursor(&mut self.cursor, selection);
@@ -219,12 +279,94 @@ impl Battle {
         }
     }
 
+    fn animate_enemies(&mut self) {
+        for display in &mut self.enemy_displays {
+            display.sprite.animate();
+        }
+    }
+
+    async fn award_items(&mut self, mctx: &mut ModeContext<'_, '_>, item: Item, amount: i32) {
+        let added = mctx.progress.add_items(item, amount);
+
+        let found = if amount == 1 {
+            let article = if item.name().starts_with('X') {
+                "an"
+            } else {
+                "a"
+            };
+            format!("{article} {}", item.name())
+        } else {
+            format!("{amount} {}", item.plural_name())
+        };
+        let msg = if added == amount {
+            format!("Coric found {found}!")
+        } else if added > 0 {
+            format!("Coric found {found},\nbut only had room for {added}.")
+        } else {
+            format!("Coric found {found},\nbut can't carry any more.")
+        };
+
+        if added > 0 {
+            mctx.audio.play_sfx(Sfx::Chime);
+        }
+        self.message_text.set_text(mctx.gctx, mctx.res, &msg);
+        self.reveal_message(mctx).await;
+        self.wait_for_confirmation(mctx).await;
+    }
+
+    async fn coric_hit_animation(&mut self, mctx: &mut ModeContext<'_, '_>, hit: Hit, damage: i32) {
+        match hit {
+            Hit::Normal => {
+                mctx.audio.play_sfx(Sfx::Hurt);
+                // Hits that take a big chunk of HP jolt the screen like critical hits do.
+                if damage >= mctx.progress.max_hp / 4 {
+                    mctx.screen_shake.shake(10, 3).await;
+                }
+                for _ in 0..5 {
+                    self.status_visible = false;
+                    wait_once().await;
+                    wait_once().await;
+                    self.status_visible = true;
+                    wait_once().await;
+                    wait_once().await;
+                }
+                self.show_status_change(mctx, &format!("{damage}"));
+            }
+            Hit::Critical => {
+                // Faster and longer than a normal hit, so it stands out.
+                mctx.audio.play_sfx(Sfx::Hurt);
+                mctx.screen_shake.shake(10, 3).await;
+                for _ in 0..12 {
+                    self.status_visible = false;
+                    wait_once().await;
+                    self.status_visible = true;
+                    wait_once().await;
+                }
+                self.show_status_change(mctx, &format!("{damage}!"));
+            }
+            Hit::Miss => self.show_status_change(mctx, "Miss"),
+        }
+
+        self.update_status(mctx);
+    }
+
     pub fn draw(&self, dctx: &mut DrawContext) {
         self.enemy_window.draw(dctx.gctx);
-        if self.enemy_visible {
-            self.enemy_sprite
-                .draw(dctx.gctx, ENEMY_X + 40, ENEMY_Y + 24);
-            self.enemy_hp_meter.draw(dctx.gctx);
+        if let Some(backdrop) = &self.backdrop {
+            backdrop.draw(dctx.gctx);
+        }
+        for display in self.enemy_displays.iter().filter(|d| d.visible) {
+            display.sprite.draw_tinted(
+                dctx.gctx,
+                display.x + display.shake,
+                ENEMY_Y + 24,
+                display.tint,
+            );
+            display.hp_meter.draw(dctx.gctx);
+            display.status_markers.draw(dctx.gctx);
+        }
+        if self.target_cursor_visible {
+            self.target_cursor.draw(dctx.gctx);
         }
         self.message_window.draw(dctx.gctx);
         self.message_text.draw(dctx.gctx);
@@ -238,6 +380,10 @@ impl Battle {
             self.mp_text.draw(dctx.gctx);
             self.hp_meter.draw(dctx.gctx);
             self.mp_meter.draw(dctx.gctx);
+            self.status_markers.draw(dctx.gctx);
+        }
+        if let Some(spell_effect) = &self.spell_effect {
+            spell_effect.draw(dctx.gctx);
         }
         if self.change_visible {
             self.change_window.draw(dctx.gctx);
@@ -245,27 +391,80 @@ impl Battle {
         }
     }
 
-    async fn enemy_hit_animation(&mut self, mctx: &mut ModeContext<'_, '_>, damage: i32) {
+    async fn enemy_hit_animation(
+        &mut self,
+        mctx: &mut ModeContext<'_, '_>,
+        target: usize,
+        hit: Hit,
+        damage: i32,
+    ) {
+        let change = match hit {
+            Hit::Normal => {
+                for _ in 0..5 {
+                    self.enemy_displays[target].visible = false;
+                    wait_once().await;
+                    wait_once().await;
+                    self.enemy_displays[target].visible = true;
+                    wait_once().await;
+                    wait_once().await;
+                }
+                format!("{damage}")
+            }
+            Hit::Critical => {
+                // The screen jolts, then the enemy shakes as it flashes white.
+                mctx.screen_shake.shake(10, 3).await;
+                for shake in [-2, 2].into_iter().cycle().take(12) {
+                    self.enemy_displays[target].tint = [1.0; 4];
+                    wait_once().await;
+                    self.enemy_displays[target].tint = [0.0; 4];
+                    self.enemy_displays[target].shake = shake;
+                    wait_once().await;
+                }
+                self.enemy_displays[target].shake = 0;
+                format!("{damage}!")
+            }
+            Hit::Miss => String::from("Miss"),
+        };
+        self.show_enemy_change(mctx, target, &change);
+    }
+
+    async fn flash_enemy(&mut self, target: usize, tint: [f32; 4]) {
         for _ in 0..5 {
-            self.enemy_visible = false;
+            self.enemy_displays[target].tint = tint;
             wait_once().await;
             wait_once().await;
-            self.enemy_visible = true;
+            self.enemy_displays[target].tint = [0.0; 4];
             wait_once().await;
             wait_once().await;
         }
-        self.enemy_hp_meter
-            .set_value(mctx.gctx, self.enemy.hp - damage);
-        self.show_change_text_at(mctx, ENEMY_X + 56, ENEMY_Y + 16, &format!("{damage}"));
+    }
+
+    // Hides enemies as they're defeated, announcing each one unless that ends the battle.
+    async fn handle_defeated_enemies(&mut self, mctx: &mut ModeContext<'_, '_>) {
+        let all_defeated = self.engine.all_enemies_defeated();
+        for i in 0..self.enemy_displays.len() {
+            let enemy = &self.engine.enemies[i];
+            if enemy.defeated() && self.enemy_displays[i].visible {
+                self.enemy_displays[i].visible = false;
+                mctx.progress.bestiary_entry_mut(&enemy.enemy.name).defeated += 1;
+                if !all_defeated {
+                    let msg = format!("{} is defeated!", enemy.name);
+                    self.message_text.set_text(mctx.gctx, mctx.res, &msg);
+                    self.reveal_message(mctx).await;
+                    self.wait_for_confirmation(mctx).await;
+                }
+            }
+        }
     }
 
     async fn item_menu(&mut self, mctx: &mut ModeContext<'_, '_>) -> Option<usize> {
         fn update_item_cursor(cursor: &mut Text, which: usize) {
             //           1         2         3
             // 012345678901234567890123456789012345
-            // .Back  .Salve     1   .Tonic     2
-            //        .XSalve    3   .XTonic    4
-            const ITEM_POSITIONS: [(i32, i32); 5] = [(0, 0), (7, 0), (22, 0), (7, 1), (22, 1)];
+            // .Back .Salve  1 .XSalve 3 .Remedy 5
+            //       .Tonic  2 .XTonic 4 .Lens   6
+            const ITEM_POSITIONS: [(i32, i32); 7] =
+                [(0, 0), (6, 0), (16, 0), (6, 1), (16, 1), (26, 0), (26, 1)];
             let x = ITEM_POSITIONS[which].0 * 6;
             let y = ITEM_POSITIONS[which].1 * 8;
             cursor.set_offset(MESSAGE_X + 8 + x, MESSAGE_Y + 24 + y);
@@ -278,11 +477,13 @@ impl Battle {
             mctx.gctx,
             mctx.res,
             &format!(
-                " Back   {:11.11}    {:11.11}\n        {:11.11}    {:11.11}",
+                " Back  {:8.8}  {:8.8}  {:8.8}\n       {:8.8}  {:8.8}  {:8.8}",
                 mctx.progress.items[0].battle_menu_entry(),
                 mctx.progress.items[1].battle_menu_entry(),
+                mctx.progress.items[4].battle_menu_entry(),
                 mctx.progress.items[2].battle_menu_entry(),
                 mctx.progress.items[3].battle_menu_entry(),
+                mctx.progress.items[5].battle_menu_entry(),
             ),
         );
 
@@ -290,7 +491,7 @@ impl Battle {
         update_item_cursor(&mut self.cursor, selection);
 
         loop {
-            self.enemy_sprite.animate();
+            self.animate_enemies();
 
             wait_once().await;
 
@@ -315,14 +516,18 @@ impl Battle {
                     0 => {}
                     1 | 2 => selection += 2,
                     3 | 4 => selection -= 2,
+                    5 => selection = 6,
+                    6 => selection = 5,
                     _ => unreachable!(),
                 }
                 update_item_cursor(&mut self.cursor, selection);
             } else if mctx.input.is_key_pressed(GameKey::Left) {
                 mctx.audio.play_sfx(Sfx::Cursor);
                 match selection {
-                    0 => selection = 2,
-                    3 => selection = 4,
+                    0 => selection = 5,
+                    3 => selection = 6,
+                    5 => selection = 2,
+                    6 => selection = 4,
                     1 | 2 | 4 => selection -= 1,
                     _ => unreachable!(),
                 }
@@ -330,8 +535,10 @@ impl Battle {
             } else if mctx.input.is_key_pressed(GameKey::Right) {
                 mctx.audio.play_sfx(Sfx::Cursor);
                 match selection {
-                    2 => selection = 0,
-                    4 => selection = 3,
+                    2 => selection = 5,
+                    4 => selection = 6,
+                    5 => selection = 0,
+                    6 => selection = 3,
                     0 | 1 | 3 => selection += 1,
                     _ => unreachable!(),
                 }
@@ -344,9 +551,9 @@ impl Battle {
         fn update_magic_cursor(cursor: &mut Text, which: usize) {
             //           1         2         3
             // 012345678901234567890123456789012345
-            // .Back  .Heal      5MP .EarthEdge 2MP
-            //        .WaterEdge 5MP .FireEdge  2MP
-            const MAGIC_POSITIONS: [(i32, i32); 5] = [(0, 0), (7, 0), (22, 0), (7, 1), (22, 1)];
+            // .Back .Heal      5MP .EarthEdge 2MP!
+            //       .WaterEdge 5MP .FireEdge  2MP
+            const MAGIC_POSITIONS: [(i32, i32); 5] = [(0, 0), (6, 0), (21, 0), (6, 1), (21, 1)];
             let x = MAGIC_POSITIONS[which].0 * 6;
             let y = MAGIC_POSITIONS[which].1 * 8;
             cursor.set_offset(MESSAGE_X + 8 + x, MESSAGE_Y + 24 + y);
@@ -354,17 +561,54 @@ impl Battle {
 
         mctx.audio.play_sfx(Sfx::Confirm);
 
-        self.message_text
-            .set_text(mctx.gctx, mctx.res, "Cast magic:");
+        // Mark known magic that a remaining enemy is known to be weak to.
+        let hints: Vec<&str> = mctx
+            .progress
+            .magic
+            .iter()
+            .map(|slot| {
+                let known_weakness = self.engine.enemies.iter().any(|e| {
+                    !e.defeated()
+                        && e.enemy.affinity(slot.magic) == Affinity::Weak
+                        && mctx
+                            .progress
+                            .bestiary
+                            .get(&e.enemy.name)
+                            .is_some_and(|entry| entry.known_affinities.contains(&slot.magic))
+                });
+                if slot.known && known_weakness {
+                    "!"
+                } else {
+                    " "
+                }
+            })
+            .collect();
+
+        let silenced = self.engine.player_statuses.has(Status::Silence);
+        self.message_text.set_text(
+            mctx.gctx,
+            mctx.res,
+            if silenced {
+                "Coric is silenced!"
+            } else if hints.contains(&"!") {
+                "Cast magic:\n(! marks a known weakness)"
+            } else {
+                "Cast magic:"
+            },
+        );
         self.menu_text.set_text(
             mctx.gctx,
             mctx.res,
             &format!(
-                " Back   {:13.13}  {:13.13}\n        {:13.13}  {:13.13}",
+                " Back  {:13.13}{} {:13.13}{}\n       {:13.13}{} {:13.13}{}",
                 mctx.progress.magic[0].battle_menu_entry(),
+                hints[0],
                 mctx.progress.magic[1].battle_menu_entry(),
+                hints[1],
                 mctx.progress.magic[2].battle_menu_entry(),
+                hints[2],
                 mctx.progress.magic[3].battle_menu_entry(),
+                hints[3],
             ),
         );
 
@@ -372,7 +616,7 @@ impl Battle {
         update_magic_cursor(&mut self.cursor, selection);
 
         loop {
-            self.enemy_sprite.animate();
+            self.animate_enemies();
 
             wait_once().await;
 
@@ -385,7 +629,7 @@ impl Battle {
                     let choice = selection - 1;
                     let magic_known = mctx.progress.magic[choice].known;
                     let mp_cost = mctx.progress.magic[choice].magic.mp_cost();
-                    if magic_known && mctx.progress.mp >= mp_cost {
+                    if magic_known && mctx.progress.mp >= mp_cost && !silenced {
                         return Some(choice);
                     } else {
                         mctx.audio.play_sfx(Sfx::Cancel);
@@ -424,6 +668,273 @@ impl Battle {
         }
     }
 
+    // Plays the spell's animation centered on the given point, waiting until it's done.
+    async fn play_spell_effect(
+        &mut self,
+        mctx: &mut ModeContext<'_, '_>,
+        magic: Magic,
+        middle_x: i32,
+        middle_y: i32,
+    ) {
+        self.spell_effect = Some(SpellEffect::new(
+            mctx.gctx, mctx.res, magic, middle_x, middle_y,
+        ));
+        while let Some(spell_effect) = self.spell_effect.as_mut().filter(|e| !e.finished()) {
+            spell_effect.animate();
+            self.animate_enemies();
+            wait_once().await;
+        }
+        self.spell_effect = None;
+    }
+
+    async fn player_turn(&mut self, mctx: &mut ModeContext<'_, '_>) -> Option<BattleEvent> {
+        let action = self
+            .action_menu(mctx, self.engine.follow_up.is_some())
+            .await;
+
+        if let PlayerAction::Run = action {
+            mctx.audio.play_sfx(Sfx::Confirm);
+
+            self.message_text
+                .set_text(mctx.gctx, mctx.res, "Coric turns to flee…");
+            self.reveal_message(mctx).await;
+            self.wait_for_confirmation(mctx).await;
+        }
+
+        match self.engine.player_turn(mctx.rng, mctx.progress, action) {
+            PlayerOutcome::Attack {
+                target,
+                hit,
+                damage,
+            } => {
+                mctx.audio.play_sfx(Sfx::Attack);
+                self.enemy_hit_animation(mctx, target, hit, damage).await;
+
+                let enemy_name = &self.engine.enemies[target].name;
+                self.message_text.set_text(
+                    mctx.gctx,
+                    mctx.res,
+                    &match hit {
+                        Hit::Normal => {
+                            format!("Coric attacks!\n{damage} HP damage to {enemy_name}.")
+                        }
+                        Hit::Critical => format!(
+                            "Coric attacks!\nA critical hit!\n{damage} HP damage to {enemy_name}."
+                        ),
+                        Hit::Miss => format!("Coric attacks!\n{enemy_name} dodges the attack!"),
+                    },
+                );
+                self.reveal_message(mctx).await;
+                self.wait_for_confirmation(mctx).await;
+            }
+
+            PlayerOutcome::Heal { hp } => {
+                mctx.audio.play_sfx(Sfx::Heal);
+                self.play_spell_effect(mctx, Magic::Heal, STATUS_X + 30, STATUS_Y + 24)
+                    .await;
+
+                self.show_status_change(mctx, &format!("{hp:+}"));
+                self.update_status(mctx);
+
+                self.message_text.set_text(
+                    mctx.gctx,
+                    mctx.res,
+                    &format!("Coric casts Heal!\n{hp} HP recovered."),
+                );
+                self.reveal_message(mctx).await;
+                self.wait_for_confirmation(mctx).await;
+            }
+
+            PlayerOutcome::Spell {
+                target,
+                magic,
+                damage,
+                affinity,
+                inflicted,
+            } => {
+                self.update_status(mctx);
+                mctx.audio.play_sfx(Sfx::Magic);
+                let x = self.enemy_displays[target].x;
+                self.play_spell_effect(mctx, magic, x + 16, ENEMY_Y + 40)
+                    .await;
+                match affinity {
+                    Affinity::Immune => self.show_enemy_change(mctx, target, "Immune"),
+                    Affinity::Absorb => {
+                        self.show_enemy_change(mctx, target, &format!("{damage:+}"))
+                    }
+                    _ => {
+                        self.flash_enemy(target, spell_flash_tint(magic)).await;
+                        self.show_enemy_change(mctx, target, &format!("{damage}"));
+                    }
+                }
+
+                let enemy_name = &self.engine.enemies[target].name;
+                let magic_name = magic.name();
+                self.message_text.set_text(
+                    mctx.gctx,
+                    mctx.res,
+                    &format!(
+                        "Coric casts {magic_name}!\n{}{}",
+                        match affinity {
+                            Affinity::Weak => format!(
+                                "{enemy_name} is weak to {magic_name}!\n\
+                                 {damage} HP damage to {enemy_name}."
+                            ),
+                            Affinity::Normal => format!("{damage} HP damage to {enemy_name}."),
+                            Affinity::Resist => format!(
+                                "{enemy_name} resists {magic_name}!\n\
+                                 {damage} HP damage to {enemy_name}."
+                            ),
+                            Affinity::Immune => format!("{enemy_name} is immune to {magic_name}!"),
+                            Affinity::Absorb => format!(
+                                "{enemy_name} absorbs {magic_name}!\n\
+                                 {damage} HP healed for {enemy_name}."
+                            ),
+                        },
+                        match inflicted {
+                            Some(status) =>
+                                format!("\n{}", status_inflicted_msg(enemy_name, status)),
+                            None => String::new(),
+                        },
+                    ),
+                );
+                self.reveal_message(mctx).await;
+                self.wait_for_confirmation(mctx).await;
+            }
+
+            PlayerOutcome::Item {
+                item,
+                hp,
+                mp,
+                cured,
+            } => {
+                mctx.audio.play_sfx(Sfx::Heal);
+
+                if hp > 0 {
+                    self.show_status_change(mctx, &format!("{hp:+}"));
+                    self.update_status(mctx);
+
+                    self.message_text.set_text(
+                        mctx.gctx,
+                        mctx.res,
+                        &format!("Coric uses {}.\n{hp} HP healed for Coric!", item.name()),
+                    );
+                    self.reveal_message(mctx).await;
+                    self.wait_for_confirmation(mctx).await;
+                }
+
+                if mp > 0 {
+                    self.show_status_change(mctx, &format!("{mp:+}MP"));
+                    self.update_status(mctx);
+
+                    self.message_text.set_text(
+                        mctx.gctx,
+                        mctx.res,
+                        &format!("Coric uses {}.\n{mp} MP healed for Coric!", item.name()),
+                    );
+                    self.reveal_message(mctx).await;
+                    self.wait_for_confirmation(mctx).await;
+                }
+
+                if cured {
+                    self.update_status(mctx);
+
+                    self.message_text.set_text(
+                        mctx.gctx,
+                        mctx.res,
+                        &format!(
+                            "Coric uses {}.\nCoric is cured of all ailments!",
+                            item.name()
+                        ),
+                    );
+                    self.reveal_message(mctx).await;
+                    self.wait_for_confirmation(mctx).await;
+                }
+            }
+
+            PlayerOutcome::Defend { mp } => {
+                mctx.audio.play_sfx(Sfx::Confirm);
+
+                self.show_status_change(mctx, &format!("{mp:+}MP"));
+                self.update_status(mctx);
+
+                self.message_text.set_text(
+                    mctx.gctx,
+                    mctx.res,
+                    &format!("Coric takes a defensive stance!\n{mp} MP recovered."),
+                );
+                self.reveal_message(mctx).await;
+                self.wait_for_confirmation(mctx).await;
+            }
+
+            PlayerOutcome::Scan { target } => {
+                mctx.audio.play_sfx(Sfx::Confirm);
+
+                let enemy = &self.engine.enemies[target];
+                self.message_text.set_text(
+                    mctx.gctx,
+                    mctx.res,
+                    &format!(
+                        "Coric uses Lens on {}.\nHP {}/{}  Attack {}  Defense {}",
+                        enemy.name,
+                        enemy.enemy.hp,
+                        enemy.max_hp,
+                        enemy.enemy.attack,
+                        enemy.enemy.defense,
+                    ),
+                );
+                self.reveal_message(mctx).await;
+                self.wait_for_confirmation(mctx).await;
+
+                let enemy = &self.engine.enemies[target];
+                let affinities: Vec<String> = enemy
+                    .enemy
+                    .affinities
+                    .iter()
+                    .filter_map(|&(magic, affinity)| match affinity {
+                        Affinity::Weak => Some(format!("Weak to {}.", magic.name())),
+                        Affinity::Normal => None,
+                        Affinity::Resist => Some(format!("Resists {}.", magic.name())),
+                        Affinity::Immune => Some(format!("Immune to {}.", magic.name())),
+                        Affinity::Absorb => Some(format!("Absorbs {}.", magic.name())),
+                    })
+                    .collect();
+                self.message_text.set_text(
+                    mctx.gctx,
+                    mctx.res,
+                    &if affinities.is_empty() {
+                        format!(
+                            "{} has no elemental\nweaknesses or resistances.",
+                            enemy.name
+                        )
+                    } else {
+                        format!("{}:\n{}", enemy.name, affinities.join("\n"))
+                    },
+                );
+                self.reveal_message(mctx).await;
+                self.wait_for_confirmation(mctx).await;
+            }
+
+            PlayerOutcome::RunFailed => {}
+
+            PlayerOutcome::RanAway => {
+                mctx.audio.set_music_volume_scripted(40);
+                self.message_text
+                    .set_text(mctx.gctx, mctx.res, "Coric ran away!");
+                self.reveal_message(mctx).await;
+                self.wait_for_confirmation(mctx).await;
+                return Some(BattleEvent::RanAway);
+            }
+        }
+
+        None
+    }
+
+    async fn reveal_message(&mut self, mctx: &mut ModeContext<'_, '_>) {
+        let chars_per_frame = mctx.battle_speed.text_speed.chars_per_frame();
+        self.message_text.reveal_at(chars_per_frame).await;
+    }
+
     fn show_change_text_at(
         &mut self,
         mctx: &mut ModeContext,
@@ -445,13 +956,77 @@ impl Battle {
         self.change_visible = true;
     }
 
+    // Updates the enemy's HP meter and shows how it changed.
+    fn show_enemy_change(&mut self, mctx: &mut ModeContext, target: usize, change: &str) {
+        let display = &mut self.enemy_displays[target];
+        display
+            .hp_meter
+            .set_value(mctx.gctx, self.engine.enemies[target].enemy.hp);
+        let x = display.x;
+        self.show_change_text_at(mctx, x + 16, ENEMY_Y + 16, change);
+    }
+
     fn show_status_change(&mut self, mctx: &mut ModeContext, msg: &str) {
-        self.show_change_text_at(mctx, STATUS_X + 26, STATUS_Y + 6, msg);
+        self.show_change_text_at(mctx, STATUS_X + 30, STATUS_Y + 6, msg);
     }
 
-    pub async fn update(&mut self, mctx: &mut ModeContext<'_, '_>) -> BattleEvent {
-        let mut follow_up: Option<(Magic, usize)> = None;
+    // Lets the player pick which enemy to target, skipping straight to the only one left if
+    // there's no choice to make.
+    async fn target_menu(&mut self, mctx: &mut ModeContext<'_, '_>) -> Option<usize> {
+        let remaining: Vec<usize> = (0..self.engine.enemies.len())
+            .filter(|&i| !self.engine.enemies[i].defeated())
+            .collect();
+        if let [only] = remaining[..] {
+            return Some(only);
+        }
+
+        mctx.audio.play_sfx(Sfx::Confirm);
+
+        self.message_text
+            .set_text(mctx.gctx, mctx.res, "Choose a target:");
+        self.menu_visible = false;
+        self.target_cursor_visible = true;
+
+        let mut selection = 0;
+        let choice = loop {
+            self.target_cursor.set_offset(
+                self.enemy_displays[remaining[selection]].x - 9,
+                ENEMY_Y + 36,
+            );
+
+            self.animate_enemies();
+
+            wait_once().await;
 
+            if mctx.input.is_key_pressed(GameKey::Cancel) {
+                break None;
+            } else if mctx.input.is_key_pressed(GameKey::Confirm) {
+                break Some(remaining[selection]);
+            } else if mctx.input.is_key_pressed(GameKey::Left) {
+                mctx.audio.play_sfx(Sfx::Cursor);
+                selection = (selection + remaining.len() - 1) % remaining.len();
+            } else if mctx.input.is_key_pressed(GameKey::Right) {
+                mctx.audio.play_sfx(Sfx::Cursor);
+                selection = (selection + 1) % remaining.len();
+            }
+        };
+
+        self.target_cursor_visible = false;
+        self.menu_visible = true;
+        choice
+    }
+
+    fn target_name(&self, target: Target) -> &str {
+        match target {
+            Target::Coric => "Coric",
+            Target::Enemy(i) => &self.engine.enemies[i].name,
+        }
+    }
+
+    pub async fn update(&mut self, mctx: &mut ModeContext<'_, '_>) -> BattleEvent {
+        for enemy in &self.engine.enemies {
+            mctx.progress.bestiary_entry_mut(&enemy.enemy.name).seen += 1;
+        }
         self.update_status(mctx);
 
         mctx.audio
@@ -462,343 +1037,155 @@ impl Battle {
             }))
             .await;
 
-        loop {
-            if let Some((magic, turns)) = follow_up {
-                follow_up = if turns > 0 {
-                    Some((magic, turns - 1))
-                } else {
-                    None
-                };
-            }
-
-            let player_damage = calc_magic_damage(
-                mctx.rng,
-                mctx.progress.attack,
-                self.enemy.defense,
-                follow_up,
-                self.enemy.weakness,
-            );
-            let mut run_after_enemy_turn = false;
+        // Only random encounters can catch either side off guard.
+        let mut opening = if self.boss_fight {
+            Opening::Normal
+        } else {
+            self.engine.roll_opening(mctx.rng, mctx.progress)
+        };
+        let several = self.engine.enemies.len() > 1;
+        let opening_msg = match opening {
+            Opening::Normal => None,
+            Opening::Preemptive if several => Some("Coric catches the enemies off guard!"),
+            Opening::Preemptive => Some("Coric catches the enemy off guard!"),
+            Opening::Ambush if several => Some("The enemies ambush Coric!"),
+            Opening::Ambush => Some("The enemy ambushes Coric!"),
+        };
+        if let Some(msg) = opening_msg {
+            self.message_text.set_text(mctx.gctx, mctx.res, msg);
+            self.reveal_message(mctx).await;
+            self.wait_for_confirmation(mctx).await;
+        }
 
-            match self.action_menu(mctx, follow_up.is_some()).await {
-                PlayerChoice::Fight => {
-                    mctx.audio.play_sfx(Sfx::Attack);
-                    self.enemy_hit_animation(mctx, player_damage).await;
-                    self.enemy.hp -= player_damage.min(self.enemy.hp);
+        loop {
+            let enemy_hp = self.engine.enemies.iter().map(|e| e.enemy.hp).collect();
+            self.recorder
+                .record_turn(mctx.progress.hp, mctx.progress.mp, enemy_hp);
 
-                    self.message_text.set_text(
-                        mctx.gctx,
-                        mctx.res,
-                        &format!(
-                            "Coric attacks!\n{player_damage} HP damage to {}.",
-                            self.enemy.name,
-                        ),
-                    );
-                    self.message_text.reveal().await;
-                    self.wait_for_confirmation(mctx).await;
-                }
+            self.engine.start_turn(mctx.rng, mctx.progress);
 
-                PlayerChoice::Magic(choice) => {
-                    mctx.progress.mp -= mctx.progress.magic[choice].magic.mp_cost();
-                    self.update_status(mctx);
+            if opening == Opening::Ambush {
+                // Coric has to wait for the enemies to take their free turn.
+            } else if self.engine.player_asleep() {
+                self.message_text
+                    .set_text(mctx.gctx, mctx.res, "Coric is fast asleep…");
+                self.reveal_message(mctx).await;
+                self.wait_for_confirmation(mctx).await;
+            } else if let Some(event) = self.player_turn(mctx).await {
+                return event;
+            }
 
-                    match mctx.progress.magic[choice].magic {
-                        Magic::Heal => {
-                            mctx.audio.play_sfx(Sfx::Heal);
-
-                            let heal_amount = (mctx.progress.max_hp + 1) / 2;
-                            self.show_status_change(mctx, &format!("{heal_amount:+}"));
-                            mctx.progress.hp =
-                                mctx.progress.max_hp.min(mctx.progress.hp + heal_amount);
-                            self.update_status(mctx);
-
-                            self.message_text.set_text(
-                                mctx.gctx,
-                                mctx.res,
-                                &format!("Coric casts Heal!\n{heal_amount} HP recovered."),
-                            );
-                        }
+            self.update_status(mctx);
+            self.handle_defeated_enemies(mctx).await;
 
-                        magic @ (Magic::EarthEdge | Magic::WaterEdge | Magic::FireEdge) => {
-                            follow_up = Some((magic, 1));
-                            let damage = calc_magic_damage(
-                                mctx.rng,
-                                mctx.progress.attack,
-                                self.enemy.defense,
-                                follow_up,
-                                self.enemy.weakness,
-                            );
-                            mctx.audio.play_sfx(Sfx::Magic);
-                            self.enemy_hit_animation(mctx, damage).await;
-                            self.enemy.hp -= damage.min(self.enemy.hp);
-
-                            self.message_text.set_text(
-                                mctx.gctx,
-                                mctx.res,
-                                &format!(
-                                    "Coric casts {}!\n{}{damage} HP damage to {}.",
-                                    magic.name(),
-                                    match (follow_up, self.enemy.weakness) {
-                                        (Some((magic, _)), Some(weakness)) if magic == weakness => {
-                                            format!(
-                                                "{} is weak to {}!\n",
-                                                self.enemy.name,
-                                                weakness.name(),
-                                            )
-                                        }
-                                        _ => String::new(),
-                                    },
-                                    self.enemy.name,
-                                ),
-                            );
-                        }
-                    }
+            if self.engine.all_enemies_defeated() {
+                self.victory(mctx).await;
+                return BattleEvent::Victory;
+            }
 
-                    self.message_text.reveal().await;
-                    self.wait_for_confirmation(mctx).await;
+            for i in 0..self.engine.enemies.len() {
+                if self.engine.enemies[i].defeated() || opening == Opening::Preemptive {
+                    continue;
                 }
 
-                PlayerChoice::Item(choice) => {
-                    mctx.audio.play_sfx(Sfx::Heal);
-
-                    mctx.progress.items[choice].amount -= 1;
-
-                    let item = mctx.progress.items[choice].item;
-                    let (heal_hp, heal_mp) = match item {
-                        Item::Salve => ((mctx.progress.max_hp * 3 + 9) / 10, 0),
-                        Item::XSalve => (mctx.progress.max_hp, 0),
-                        Item::Tonic => (0, (mctx.progress.max_mp * 3 + 9) / 10),
-                        Item::XTonic => (0, mctx.progress.max_mp),
-                    };
+                let enemy_outcome = self.engine.enemy_turn(mctx.rng, mctx.progress, i);
+                let enemy = &self.engine.enemies[i];
 
-                    if heal_hp > 0 {
-                        self.show_status_change(mctx, &format!("{heal_hp:+}"));
-                        mctx.progress.hp = mctx.progress.max_hp.min(mctx.progress.hp + heal_hp);
-                        self.update_status(mctx);
-
-                        self.message_text.set_text(
-                            mctx.gctx,
-                            mctx.res,
-                            &format!(
-                                "Coric uses {}.\n{heal_hp} HP healed for Coric!",
-                                item.name()
-                            ),
-                        );
-                        self.message_text.reveal().await;
-                        self.wait_for_confirmation(mctx).await;
-                    }
-
-                    if heal_mp > 0 {
-                        self.show_status_change(mctx, &format!("{heal_mp:+}MP"));
-                        mctx.progress.mp = mctx.progress.max_mp.min(mctx.progress.mp + heal_mp);
-                        self.update_status(mctx);
-
-                        self.message_text.set_text(
-                            mctx.gctx,
-                            mctx.res,
-                            &format!(
-                                "Coric uses {}.\n{heal_mp} MP healed for Coric!",
-                                item.name()
-                            ),
-                        );
-                        self.message_text.reveal().await;
+                if enemy_outcome.phase_changed {
+                    if let Some(phase) = enemy.current_phase() {
+                        if let Some(animation) = &phase.animation {
+                            self.enemy_displays[i].sprite.start_animation(animation);
+                        }
+                        let phase_msg = format!("{} {}", enemy.name, phase.msg);
+                        self.message_text.set_text(mctx.gctx, mctx.res, &phase_msg);
+                        self.reveal_message(mctx).await;
                         self.wait_for_confirmation(mctx).await;
                     }
                 }
 
-                PlayerChoice::Run => {
-                    mctx.audio.play_sfx(Sfx::Confirm);
+                let mut enemy_msg =
+                    format!("{} {}", self.engine.enemies[i].name, enemy_outcome.msg);
 
-                    self.message_text
-                        .set_text(mctx.gctx, mctx.res, "Coric turns to flee…");
-                    self.message_text.reveal().await;
-                    self.wait_for_confirmation(mctx).await;
+                if let Some(damage) = enemy_outcome.damage {
+                    self.coric_hit_animation(mctx, enemy_outcome.hit, damage)
+                        .await;
 
-                    let hp_run_threshold = self.enemy_max_hp - self.enemy.hp + player_damage;
-                    if mctx.rng.random(self.enemy_max_hp as u32) < hp_run_threshold as u32 {
-                        mctx.audio.set_music_volume_scripted(40);
-                        self.message_text
-                            .set_text(mctx.gctx, mctx.res, "Coric ran away!");
-                        self.message_text.reveal().await;
-                        self.wait_for_confirmation(mctx).await;
-                        return BattleEvent::RanAway;
+                    // Kept to one line, since enemy messages can already take up the rest.
+                    if enemy_outcome.hit == Hit::Critical {
+                        enemy_msg.push_str(&format!("\nCritical! {damage} HP damage to Coric."));
                     } else {
-                        run_after_enemy_turn = true;
+                        enemy_msg.push_str(&format!("\n{damage} HP damage to Coric."));
                     }
-                }
-            }
-
-            self.update_status(mctx);
-
-            if self.enemy.hp <= 0 {
-                self.enemy_visible = false;
-
-                mctx.audio.set_music_volume_scripted(40);
+                } else if enemy_outcome.hit == Hit::Miss {
+                    self.coric_hit_animation(mctx, Hit::Miss, 0).await;
 
-                self.message_text.set_text(
-                    mctx.gctx,
-                    mctx.res,
-                    &format!(
-                        "{} is defeated!\nCoric gained {} XP!",
-                        self.enemy.name, self.enemy.exp,
-                    ),
-                );
-                self.message_text.reveal().await;
-                self.wait_for_confirmation(mctx).await;
+                    enemy_msg.push_str("\nCoric dodges the attack!");
+                }
 
-                mctx.progress.exp += self.enemy.exp;
-                while mctx.progress.gain_level_from_exp() {
+                if let Some(status) = enemy_outcome.inflicted {
                     self.update_status(mctx);
 
-                    let mut msg = format!("Coric is now level {}!", mctx.progress.level);
-                    let new_rank = player_rank(mctx.progress.level);
-                    if new_rank != player_rank(mctx.progress.level - 1) {
-                        msg.push_str("\nCoric becomes a ");
-                        msg.push_str(new_rank);
-                        msg.push('!');
-                    }
-
-                    mctx.audio.play_sfx(Sfx::LevelUp);
-                    self.message_text.set_text(mctx.gctx, mctx.res, &msg);
-                    self.message_text.reveal().await;
-                    self.wait_for_confirmation(mctx).await;
+                    enemy_msg.push('\n');
+                    enemy_msg.push_str(&status_inflicted_msg("Coric", status));
                 }
 
-                // Chance for an item to drop.
-                if mctx.rng.random(10) == 0 {
-                    let item_roll = mctx.rng.random(18);
+                self.message_text.set_text(mctx.gctx, mctx.res, &enemy_msg);
+                self.reveal_message(mctx).await;
+                self.wait_for_confirmation(mctx).await;
 
-                    if item_roll < 9 {
-                        let salves = mctx
-                            .progress
-                            .items
-                            .iter_mut()
-                            .find(|i| matches!(i.item, Item::Salve))
-                            .expect("salve item slot");
-                        if salves.amount < 9 {
-                            salves.amount += 1;
-                            mctx.audio.play_sfx(Sfx::Chime);
-                            self.message_text
-                                .set_text(mctx.gctx, mctx.res, "Coric found a Salve!");
-                            self.message_text.reveal().await;
-                            self.wait_for_confirmation(mctx).await;
-                        }
-                    } else if item_roll < 14 {
-                        let tonics = mctx
-                            .progress
-                            .items
-                            .iter_mut()
-                            .find(|i| matches!(i.item, Item::Tonic))
-                            .expect("tonic item slot");
-                        if tonics.amount < 9 {
-                            tonics.amount += 1;
-                            mctx.audio.play_sfx(Sfx::Chime);
-                            self.message_text
-                                .set_text(mctx.gctx, mctx.res, "Coric found a Tonic!");
-                            self.message_text.reveal().await;
-                            self.wait_for_confirmation(mctx).await;
-                        }
-                    } else if item_roll < 17 {
-                        let xsalves = mctx
-                            .progress
-                            .items
-                            .iter_mut()
-                            .find(|i| matches!(i.item, Item::XSalve))
-                            .expect("xsalve item slot");
-                        if xsalves.amount < 9 {
-                            xsalves.amount += 1;
-                            mctx.audio.play_sfx(Sfx::Chime);
-                            self.message_text.set_text(
-                                mctx.gctx,
-                                mctx.res,
-                                "Coric found an XSalve!",
-                            );
-                            self.message_text.reveal().await;
-                            self.wait_for_confirmation(mctx).await;
-                        }
-                    } else {
-                        let xtonics = mctx
-                            .progress
-                            .items
-                            .iter_mut()
-                            .find(|i| matches!(i.item, Item::XTonic))
-                            .expect("xtonic item slot");
-                        if xtonics.amount < 9 {
-                            xtonics.amount += 1;
-                            mctx.audio.play_sfx(Sfx::Chime);
-                            self.message_text.set_text(
-                                mctx.gctx,
-                                mctx.res,
-                                "Coric found an XTonic!",
-                            );
-                            self.message_text.reveal().await;
-                            self.wait_for_confirmation(mctx).await;
-                        }
-                    }
+                if mctx.progress.hp <= 0 {
+                    mctx.audio.set_music_volume_scripted(40);
+                    return BattleEvent::Defeat;
                 }
-
-                return BattleEvent::Victory;
             }
+            opening = Opening::Normal;
 
-            let mut enemy_action = EnemyAction {
-                chance: 100,
-                msg: "attacks!",
-                damage_factor: Some(1.0),
-            };
-
-            let mut enemy_roll = mctx.rng.random(100);
-            for action in self.enemy.actions {
-                if enemy_roll < action.chance {
-                    enemy_action = action.clone();
-                    break;
-                } else {
-                    enemy_roll -= action.chance;
-                }
+            if self.engine.fleeing() {
+                mctx.audio.set_music_volume_scripted(40);
+                self.message_text
+                    .set_text(mctx.gctx, mctx.res, "Coric ran away!");
+                self.reveal_message(mctx).await;
+                self.wait_for_confirmation(mctx).await;
+                return BattleEvent::RanAway;
             }
 
-            let mut enemy_msg = format!("{} {}", self.enemy.name, enemy_action.msg);
-
-            if let Some(damage_factor) = enemy_action.damage_factor {
-                let base_damage =
-                    calc_base_damage(self.enemy.attack, mctx.progress.defense) * damage_factor;
-                let damage = base_damage.trunc() as i32
-                    + if (mctx.rng.random(100) as f32) < base_damage.fract() * 100.0 {
-                        1
-                    } else {
-                        0
-                    };
-
-                mctx.audio.play_sfx(Sfx::Hurt);
-                for _ in 0..5 {
-                    self.status_visible = false;
-                    wait_once().await;
-                    wait_once().await;
-                    self.status_visible = true;
-                    wait_once().await;
-                    wait_once().await;
-                }
-
-                self.show_status_change(mctx, &format!("{damage}"));
-                mctx.progress.hp -= damage.min(mctx.progress.hp);
-                self.update_status(mctx);
-
-                enemy_msg.push_str(&format!("\n{damage} HP damage to Coric."));
+            for event in self.engine.end_turn(mctx.progress) {
+                let msg = match event {
+                    StatusEvent::Poison { target, damage } => {
+                        match target {
+                            Target::Coric => {
+                                self.coric_hit_animation(mctx, Hit::Normal, damage).await
+                            }
+                            Target::Enemy(i) => {
+                                mctx.audio.play_sfx(Sfx::Attack);
+                                self.enemy_hit_animation(mctx, i, Hit::Normal, damage).await;
+                            }
+                        }
+                        format!(
+                            "{} takes {damage} HP damage\nfrom poison.",
+                            self.target_name(target),
+                        )
+                    }
+                    StatusEvent::WoreOff { target, status } => {
+                        self.update_status(mctx);
+                        status_wore_off_msg(self.target_name(target), status)
+                    }
+                };
+                self.message_text.set_text(mctx.gctx, mctx.res, &msg);
+                self.reveal_message(mctx).await;
+                self.wait_for_confirmation(mctx).await;
             }
 
-            self.message_text.set_text(mctx.gctx, mctx.res, &enemy_msg);
-            self.message_text.reveal().await;
-            self.wait_for_confirmation(mctx).await;
-
             if mctx.progress.hp <= 0 {
                 mctx.audio.set_music_volume_scripted(40);
                 return BattleEvent::Defeat;
-            } else if run_after_enemy_turn {
-                mctx.audio.set_music_volume_scripted(40);
-                self.message_text
-                    .set_text(mctx.gctx, mctx.res, "Coric ran away!");
-                self.message_text.reveal().await;
-                self.wait_for_confirmation(mctx).await;
-                return BattleEvent::RanAway;
+            }
+
+            self.handle_defeated_enemies(mctx).await;
+
+            if self.engine.all_enemies_defeated() {
+                self.victory(mctx).await;
+                return BattleEvent::Victory;
             }
         }
     }
@@ -812,51 +1199,135 @@ impl Battle {
             .set_value_and_max(mctx.gctx, mctx.progress.hp, mctx.progress.max_hp);
         self.mp_meter
             .set_value_and_max(mctx.gctx, mctx.progress.mp, mctx.progress.max_mp);
+        self.status_markers.set_text(
+            mctx.gctx,
+            mctx.res,
+            &status_markers(&self.engine.player_statuses, "\n"),
+        );
+        for (display, enemy) in self.enemy_displays.iter_mut().zip(&self.engine.enemies) {
+            display.status_markers.set_text(
+                mctx.gctx,
+                mctx.res,
+                &status_markers(&enemy.statuses, ""),
+            );
+        }
+    }
+
+    async fn victory(&mut self, mctx: &mut ModeContext<'_, '_>) {
+        mctx.audio.set_music_volume_scripted(40);
+
+        let exp: i32 = self.engine.enemies.iter().map(|e| e.enemy.exp).sum();
+        let defeated = match &self.engine.enemies[..] {
+            [enemy] => format!("{} is", enemy.name),
+            _ => String::from("The enemies are"),
+        };
+        self.message_text.set_text(
+            mctx.gctx,
+            mctx.res,
+            &format!("{defeated} defeated!\nCoric gained {exp} XP!"),
+        );
+        self.reveal_message(mctx).await;
+        self.wait_for_confirmation(mctx).await;
+
+        mctx.progress.exp += exp;
+        while mctx.progress.gain_level_from_exp() {
+            self.update_status(mctx);
+
+            let mut msg = format!("Coric is now level {}!", mctx.progress.level);
+            let new_rank = player_rank(mctx.progress.level);
+            if new_rank != player_rank(mctx.progress.level - 1) {
+                msg.push_str("\nCoric becomes a ");
+                msg.push_str(new_rank);
+                msg.push('!');
+            }
+
+            mctx.audio.play_sfx(Sfx::LevelUp);
+            self.message_text.set_text(mctx.gctx, mctx.res, &msg);
+            self.reveal_message(mctx).await;
+            self.wait_for_confirmation(mctx).await;
+        }
+
+        // Each enemy rolls for its own drops, on top of any reward that it always gives.
+        let mut found = Vec::new();
+        for enemy in &self.engine.enemies {
+            found.extend(
+                enemy
+                    .enemy
+                    .reward
+                    .map(|reward| (reward.item, reward.amount)),
+            );
+            for drop in &enemy.enemy.drops {
+                if mctx.rng.random(100) < drop.chance {
+                    found.push((drop.item, drop.amount));
+                }
+            }
+        }
+        for (item, amount) in found {
+            self.award_items(mctx, item, amount).await;
+        }
     }
 
     async fn wait_for_confirmation(&mut self, mctx: &mut ModeContext<'_, '_>) {
+        let auto_advance_frames = mctx.battle_speed.auto_advance_frames();
+        let mut frames = 0;
         while !mctx.input.is_key_pressed(GameKey::Confirm) {
-            self.enemy_sprite.animate();
+            if auto_advance_frames.is_some_and(|f| frames >= f) {
+                self.change_visible = false;
+                return;
+            }
+            self.animate_enemies();
             wait_once().await;
+            frames += 1;
         }
         self.change_visible = false;
         mctx.audio.play_sfx(Sfx::Cursor);
     }
 }
 
-fn calc_base_damage(attack: i32, defense: i32) -> f32 {
-    let attack = attack as f32;
-    let defense = defense as f32;
-    if attack * attack < defense {
-        1.0
-    } else if attack < defense {
-        attack * attack / defense
-    } else {
-        attack * 2.0 - defense
+impl BattleEvent {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Defeat => "Defeat",
+            Self::RanAway => "RanAway",
+            Self::Victory => "Victory",
+        }
     }
 }
 
-fn calc_magic_damage(
-    rng: &mut Rng,
-    attack: i32,
-    defense: i32,
-    follow_up: Option<(Magic, usize)>,
-    weakness: Option<Magic>,
-) -> i32 {
-    let base_damage = calc_base_damage(attack, defense);
-    let bonus: f32 = match follow_up {
-        Some((magic, turns)) => {
-            let weak = weakness.map(|m| m == magic).unwrap_or(false);
-            let which = if weak { turns + 1 } else { turns };
-            [0.5, 1.0, 2.0][which.min(2)]
-        }
-        None => 0.0,
-    };
-    let damage = base_damage * (1.0 + bonus);
-    damage.trunc() as i32
-        + if (rng.random(100) as f32) < damage.fract() * 100.0 {
-            1
-        } else {
-            0
-        }
+fn status_inflicted_msg(name: &str, status: Status) -> String {
+    match status {
+        Status::Poison => format!("{name} is poisoned!"),
+        Status::Sleep => format!("{name} falls asleep!"),
+        Status::Silence => format!("{name} is silenced!"),
+        Status::Weaken => format!("{name} is weakened!"),
+    }
+}
+
+// One letter for each active status, in a fixed order.
+fn status_markers(statuses: &StatusEffects, separator: &str) -> String {
+    Status::ALL
+        .iter()
+        .map(|&status| {
+            if !statuses.has(status) {
+                " "
+            } else {
+                match status {
+                    Status::Poison => "P",
+                    Status::Sleep => "Z",
+                    Status::Silence => "S",
+                    Status::Weaken => "W",
+                }
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(separator)
+}
+
+fn status_wore_off_msg(name: &str, status: Status) -> String {
+    match status {
+        Status::Poison => format!("The poison wears off for {name}."),
+        Status::Sleep => format!("{name} wakes up!"),
+        Status::Silence => format!("{name} can cast magic again."),
+        Status::Weaken => format!("{name} regains strength."),
+    }
 }
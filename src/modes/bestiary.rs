@@ -0,0 +1,222 @@
+use crate::async_utils::wait_once;
+use crate::audio::*;
+use crate::contexts::*;
+use crate::enemy::*;
+use crate::input::*;
+use crate::progress::*;
+use crate::resources::*;
+use crate::sprite::*;
+use crate::text::*;
+use crate::window::*;
+
+use miniquad::GlContext;
+
+const LIST_X: i32 = 16;
+const LIST_Y: i32 = 16;
+const LIST_WIDTH: i32 = 104;
+const LIST_HEIGHT: i32 = 144;
+const DETAIL_X: i32 = LIST_X + LIST_WIDTH;
+const DETAIL_Y: i32 = LIST_Y;
+const DETAIL_WIDTH: i32 = 184;
+const DETAIL_HEIGHT: i32 = LIST_HEIGHT;
+const CURSOR_X: i32 = LIST_X + 8;
+const CURSOR_Y: i32 = LIST_Y + 8;
+const VISIBLE_ENTRIES: i32 = 16;
+
+pub struct Bestiary {
+    list_window: Window,
+    list_text: Text,
+    cursor: Text,
+    detail_window: Window,
+    detail_text: Text,
+    enemies: Vec<Enemy>,
+    sprites: Vec<Sprite>,
+    seen: Vec<bool>,
+    entries: Vec<String>,
+    selection: i32,
+    top: i32,
+}
+
+pub enum BestiaryEvent {
+    Done,
+}
+
+impl Bestiary {
+    pub fn new(gctx: &mut GlContext, res: &Resources, progress: &Progress) -> Self {
+        let enemies = res.enemies.all().to_vec();
+        let sprites = enemies
+            .iter()
+            .map(|enemy| {
+                let mut sprite = Sprite::new(gctx, res, &enemy.sprite_path);
+                sprite.start_animation("idle");
+                sprite
+            })
+            .collect();
+        let seen: Vec<bool> = enemies
+            .iter()
+            .map(|enemy| progress.bestiary.contains_key(&enemy.name))
+            .collect();
+        let entries = std::iter::once(String::from("Back"))
+            .chain(
+                enemies
+                    .iter()
+                    .zip(&seen)
+                    .enumerate()
+                    .map(|(i, (enemy, &seen))| {
+                        format!("{:2} {}", i + 1, if seen { &enemy.name } else { "??????" })
+                    }),
+            )
+            .collect();
+
+        Self {
+            list_window: Window::new(gctx, res, LIST_X, LIST_Y, LIST_WIDTH, LIST_HEIGHT),
+            list_text: Text::new(res, LIST_X + 14, LIST_Y + 8),
+            cursor: Text::from_str(gctx, res, CURSOR_X, CURSOR_Y, "►"),
+            detail_window: Window::new(gctx, res, DETAIL_X, DETAIL_Y, DETAIL_WIDTH, DETAIL_HEIGHT),
+            detail_text: Text::new(res, DETAIL_X + 8, DETAIL_Y + 56),
+            enemies,
+            sprites,
+            seen,
+            entries,
+            selection: 0,
+            top: 0,
+        }
+    }
+
+    pub fn draw(&self, dctx: &mut DrawContext) {
+        self.list_window.draw(dctx.gctx);
+        self.list_text.draw(dctx.gctx);
+        self.cursor.draw(dctx.gctx);
+        self.detail_window.draw(dctx.gctx);
+        self.detail_text.draw(dctx.gctx);
+        if let Some(sprite) = self.selected_sprite() {
+            sprite.draw(dctx.gctx, DETAIL_X + (DETAIL_WIDTH - 32) / 2, DETAIL_Y + 16);
+        }
+    }
+
+    // The sprite of the selected enemy, if it has been seen.
+    fn selected_sprite(&self) -> Option<&Sprite> {
+        let i = usize::try_from(self.selection - 1).ok()?;
+        self.seen[i].then(|| &self.sprites[i])
+    }
+
+    pub async fn update(&mut self, mctx: &mut ModeContext<'_, '_>) -> BestiaryEvent {
+        let num_entries = self.entries.len() as i32;
+
+        self.update_list_text(mctx);
+        self.update_detail_text(mctx);
+        self.cursor
+            .set_offset(CURSOR_X, CURSOR_Y + 8 * (self.selection - self.top));
+
+        loop {
+            wait_once().await;
+
+            if let Ok(i) = usize::try_from(self.selection - 1) {
+                self.sprites[i].animate();
+            }
+
+            if mctx.input.is_key_pressed(GameKey::Cancel)
+                || (mctx.input.is_key_pressed(GameKey::Confirm) && self.selection == 0)
+            {
+                mctx.audio.play_sfx(Sfx::Cancel);
+                return BestiaryEvent::Done;
+            } else if mctx.input.is_key_pressed(GameKey::Up)
+                || mctx.input.is_key_pressed(GameKey::Down)
+            {
+                mctx.audio.play_sfx(Sfx::Cursor);
+                if mctx.input.is_key_pressed(GameKey::Up) {
+                    self.selection = (self.selection + num_entries - 1) % num_entries;
+                } else {
+                    self.selection = (self.selection + 1) % num_entries;
+                }
+                let new_top = self
+                    .top
+                    .clamp(self.selection - VISIBLE_ENTRIES + 1, self.selection);
+                if new_top != self.top {
+                    self.top = new_top;
+                    self.update_list_text(mctx);
+                }
+                self.update_detail_text(mctx);
+                self.cursor
+                    .set_offset(CURSOR_X, CURSOR_Y + 8 * (self.selection - self.top));
+            }
+        }
+    }
+
+    fn update_detail_text(&mut self, mctx: &mut ModeContext) {
+        let text = match usize::try_from(self.selection - 1) {
+            Ok(i) => {
+                let enemy = &self.enemies[i];
+                match mctx.progress.bestiary.get(&enemy.name) {
+                    Some(entry) => format!(
+                        "{:^28}\
+                       \n\n{:<8}{:>5}  {:<8}{:>5}\
+                         \n{:<8}{:>5}  {:<8}{:>5}\
+                       \n\n{}\
+                       \n\n{:<8}{:>5}  {:<8}{:>5}",
+                        enemy.name,
+                        "HP",
+                        enemy.hp,
+                        "EXP",
+                        enemy.exp,
+                        "Attack",
+                        enemy.attack,
+                        "Defense",
+                        enemy.defense,
+                        Magic::EDGES
+                            .iter()
+                            .map(|&magic| format!(
+                                "{:<10}{:>18}",
+                                magic.name(),
+                                if entry.known_affinities.contains(&magic) {
+                                    enemy.affinity(magic).name()
+                                } else {
+                                    "???"
+                                },
+                            ))
+                            .collect::<Vec<_>>()
+                            .join("\n"),
+                        "Seen",
+                        entry.seen,
+                        "Defeated",
+                        entry.defeated,
+                    ),
+                    None => format!("{:^28}", "??????"),
+                }
+            }
+            Err(_) => {
+                let seen = self.seen.iter().filter(|&&seen| seen).count();
+                let defeated = self
+                    .enemies
+                    .iter()
+                    .filter(|enemy| {
+                        mctx.progress
+                            .bestiary
+                            .get(&enemy.name)
+                            .is_some_and(|entry| entry.defeated > 0)
+                    })
+                    .count();
+                format!(
+                    "{:<8}{:>12} / {:>2}\n\n{:<8}{:>12} / {:>2}",
+                    "Seen",
+                    seen,
+                    self.enemies.len(),
+                    "Defeated",
+                    defeated,
+                    self.enemies.len(),
+                )
+            }
+        };
+        self.detail_text.set_text(mctx.gctx, mctx.res, &text);
+    }
+
+    fn update_list_text(&mut self, mctx: &mut ModeContext) {
+        let num_entries = self.entries.len() as i32;
+        let visible_end = (self.top + VISIBLE_ENTRIES).min(num_entries);
+        self.list_text.set_text(
+            mctx.gctx,
+            mctx.res,
+            &self.entries[self.top as usize..visible_end as usize].join("\n"),
+        );
+    }
+}
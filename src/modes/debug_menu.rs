@@ -20,12 +20,12 @@ pub enum DebugMenuEvent {
     Cancel,
     GainLevel,
     Battle(i32),
+    ReplayBattle,
     SetWeapon(Option<Weapon>),
     SetArmor(Option<Armor>),
     GetItems,
     LearnAllMagic,
     ResetStepCounts,
-    ChangeFlag(i32),
     Warp {
         level_id: &'static str,
         x: i32,
@@ -61,7 +61,8 @@ impl DebugMenu {
              Fire Castle\n\
              Earth Spirit\n\
              Water Spirit\n\
-             Fire Spirit",
+             Fire Spirit\n\
+             Last Replay",
         );
 
         let mut selection: i32 = 0;
@@ -85,14 +86,14 @@ impl DebugMenu {
             } else if mctx.input.is_key_pressed(GameKey::Up) {
                 mctx.audio.play_sfx(Sfx::Cursor);
                 if selection == 0 {
-                    selection = 9;
+                    selection = 10;
                 } else {
                     selection -= 1;
                 }
                 self.update_cursor_pos(selection);
             } else if mctx.input.is_key_pressed(GameKey::Down) {
                 mctx.audio.play_sfx(Sfx::Cursor);
-                if selection == 9 {
+                if selection == 10 {
                     selection = 0;
                 } else {
                     selection += 1;
@@ -108,57 +109,100 @@ impl DebugMenu {
         self.cursor.draw(dctx.gctx);
     }
 
-    async fn flags_menu(&mut self, mctx: &mut ModeContext<'_, '_>) -> Option<i32> {
+    // Flags and variables are changed in place, so several can be changed in one visit.
+    async fn flags_menu(&mut self, mctx: &mut ModeContext<'_, '_>) {
+        const VISIBLE_ENTRIES: i32 = 11;
+
+        fn update_flags_text(
+            text: &mut Text,
+            mctx: &mut ModeContext,
+            flag_names: &[String],
+            var_names: &[String],
+            top: i32,
+        ) {
+            let entries: Vec<String> = std::iter::once(String::from("Back"))
+                .chain(
+                    flag_names
+                        .iter()
+                        .map(|name| format!("{name:15.15}{:>5}", mctx.progress.flag(name))),
+                )
+                .chain(
+                    var_names
+                        .iter()
+                        .map(|name| format!("{name:15.15}{:>5}", mctx.progress.var(name))),
+                )
+                .skip(top as usize)
+                .take(VISIBLE_ENTRIES as usize)
+                .collect();
+            text.set_text(mctx.gctx, mctx.res, &entries.join("\n"));
+        }
+
         mctx.audio.play_sfx(Sfx::Confirm);
 
-        self.text.set_text(
-            mctx.gctx,
-            mctx.res,
-            &format!(
-                "Back\n\
-                 earth_defeated: {}\n\
-                 water_defeated: {}\n\
-                 fire_defeated:  {}",
-                mctx.progress.earth_defeated,
-                mctx.progress.water_defeated,
-                mctx.progress.fire_defeated,
-            ),
-        );
+        let flag_names = mctx.progress.flag_names();
+        let var_names = mctx.progress.var_names();
+        let num_entries = 1 + (flag_names.len() + var_names.len()) as i32;
+        let num_flags = flag_names.len() as i32;
 
+        // Scroll the list if there are more entries than fit in the window.
+        let mut top: i32 = 0;
         let mut selection: i32 = 0;
 
+        update_flags_text(&mut self.text, mctx, &flag_names, &var_names, top);
         self.update_cursor_pos(selection);
 
         loop {
             wait_once().await;
 
+            let var_name =
+                (selection > num_flags).then(|| &var_names[(selection - num_flags - 1) as usize]);
+
             if mctx.input.is_key_pressed(GameKey::Cancel) {
                 mctx.audio.play_sfx(Sfx::Cancel);
-                return None;
+                return;
             } else if mctx.input.is_key_pressed(GameKey::Confirm) {
-                return match selection {
-                    0 => {
-                        mctx.audio.play_sfx(Sfx::Cancel);
-                        None
-                    }
-                    s => Some(s - 1),
-                };
-            } else if mctx.input.is_key_pressed(GameKey::Up) {
-                mctx.audio.play_sfx(Sfx::Cursor);
                 if selection == 0 {
-                    selection = 3;
+                    mctx.audio.play_sfx(Sfx::Cancel);
+                    return;
+                }
+                mctx.audio.play_sfx(Sfx::Confirm);
+                if let Some(var_name) = var_name {
+                    let value = mctx.progress.var(var_name).saturating_add(1);
+                    mctx.progress.set_var(var_name, value);
                 } else {
-                    selection -= 1;
+                    let flag_name = &flag_names[(selection - 1) as usize];
+                    let value = !mctx.progress.flag(flag_name);
+                    mctx.progress.set_flag(flag_name, value);
                 }
-                self.update_cursor_pos(selection);
-            } else if mctx.input.is_key_pressed(GameKey::Down) {
+                update_flags_text(&mut self.text, mctx, &flag_names, &var_names, top);
+            } else if let Some(var_name) = var_name.filter(|_| {
+                mctx.input.is_key_pressed(GameKey::Left)
+                    || mctx.input.is_key_pressed(GameKey::Right)
+            }) {
                 mctx.audio.play_sfx(Sfx::Cursor);
-                if selection == 3 {
-                    selection = 0;
+                let delta = if mctx.input.is_key_pressed(GameKey::Left) {
+                    -1
                 } else {
-                    selection += 1;
+                    1
+                };
+                let value = mctx.progress.var(var_name).saturating_add(delta);
+                mctx.progress.set_var(var_name, value);
+                update_flags_text(&mut self.text, mctx, &flag_names, &var_names, top);
+            } else if mctx.input.is_key_pressed(GameKey::Up)
+                || mctx.input.is_key_pressed(GameKey::Down)
+            {
+                mctx.audio.play_sfx(Sfx::Cursor);
+                if mctx.input.is_key_pressed(GameKey::Up) {
+                    selection = (selection + num_entries - 1) % num_entries;
+                } else {
+                    selection = (selection + 1) % num_entries;
                 }
-                self.update_cursor_pos(selection);
+                let new_top = top.clamp(selection - VISIBLE_ENTRIES + 1, selection);
+                if new_top != top {
+                    top = new_top;
+                    update_flags_text(&mut self.text, mctx, &flag_names, &var_names, top);
+                }
+                self.update_cursor_pos(selection - top);
             }
         }
     }
@@ -397,7 +441,10 @@ impl DebugMenu {
                 let choice = match selection {
                     0 => Some(DebugMenuEvent::Cancel),
                     1 => Some(DebugMenuEvent::GainLevel),
-                    2 => self.battle_menu(mctx).await.map(DebugMenuEvent::Battle),
+                    2 => self.battle_menu(mctx).await.map(|battle| match battle {
+                        9 => DebugMenuEvent::ReplayBattle,
+                        _ => DebugMenuEvent::Battle(battle),
+                    }),
                     3 => self
                         .set_weapon_menu(mctx)
                         .await
@@ -412,7 +459,10 @@ impl DebugMenu {
                         .step_counts_menu(mctx)
                         .await
                         .then_some(DebugMenuEvent::ResetStepCounts),
-                    8 => self.flags_menu(mctx).await.map(DebugMenuEvent::ChangeFlag),
+                    8 => {
+                        self.flags_menu(mctx).await;
+                        None
+                    }
                     9 => self.warp_menu(mctx).await,
                     10 => Some(DebugMenuEvent::Quit),
                     _ => unreachable!(),
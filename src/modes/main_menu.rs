@@ -11,21 +11,22 @@ use crate::window::*;
 use miniquad::GlContext;
 
 const TOP_X: i32 = 44;
-const TOP_Y: i32 = 25;
+const TOP_Y: i32 = 21;
 const TOP_WIDTH: i32 = 25 * 6 + 16;
 const TOP_HEIGHT: i32 = 3 * 8 + 5 + 16;
 const BOTTOM_X: i32 = TOP_X;
 const BOTTOM_Y: i32 = TOP_Y + TOP_HEIGHT;
 const BOTTOM_WIDTH: i32 = TOP_WIDTH;
-const BOTTOM_HEIGHT: i32 = 8 * 8 + 16;
+const BOTTOM_HEIGHT: i32 = 9 * 8 + 16;
 const MENU_X: i32 = TOP_X + TOP_WIDTH;
 const MENU_Y: i32 = TOP_Y;
-const MENU_WIDTH: i32 = 66;
-const MENU_HEIGHT: i32 = 72;
+const MENU_WIDTH: i32 = 70;
+const MENU_HEIGHT: i32 = 104;
 
 pub struct MainMenu {
     selection: i32,
     status_only: bool,
+    stats_shown: bool,
     top_window: Window,
     name_text: Text,
     level_text: Text,
@@ -45,6 +46,7 @@ pub struct MainMenu {
 }
 
 pub enum MainMenuEvent {
+    Bestiary,
     Done,
     Options,
 }
@@ -138,13 +140,14 @@ impl MainMenu {
             res,
             MENU_X + 14,
             MENU_Y + 8,
-            "Return\n\nMagic\n\nItem\n\nOptions",
+            "Return\n\nMagic\n\nItem\n\nStats\n\nBestiary\n\nOptions",
         );
         let menu_cursor = Text::from_str(gctx, res, MENU_X + 8, MENU_Y + 8, "►");
 
         Self {
             selection: 0,
             status_only,
+            stats_shown: false,
             top_window,
             name_text,
             level_text,
@@ -216,6 +219,7 @@ impl MainMenu {
                                 if mctx.progress.hp < mctx.progress.max_hp {
                                     mctx.audio.play_sfx(Sfx::Heal);
                                     mctx.progress.items[choice].amount -= 1;
+                                    mctx.progress.stats.items_used += 1;
                                     let heal_hp = (mctx.progress.max_hp * 3 + 9) / 10;
                                     mctx.progress.hp =
                                         mctx.progress.max_hp.min(mctx.progress.hp + heal_hp);
@@ -227,6 +231,7 @@ impl MainMenu {
                                 if mctx.progress.hp < mctx.progress.max_hp {
                                     mctx.audio.play_sfx(Sfx::Heal);
                                     mctx.progress.items[choice].amount -= 1;
+                                    mctx.progress.stats.items_used += 1;
                                     mctx.progress.hp = mctx.progress.max_hp;
                                 } else {
                                     mctx.audio.play_sfx(Sfx::Cancel);
@@ -236,6 +241,7 @@ impl MainMenu {
                                 if mctx.progress.mp < mctx.progress.max_mp {
                                     mctx.audio.play_sfx(Sfx::Heal);
                                     mctx.progress.items[choice].amount -= 1;
+                                    mctx.progress.stats.items_used += 1;
                                     let heal_mp = (mctx.progress.max_mp * 3 + 9) / 10;
                                     mctx.progress.mp =
                                         mctx.progress.max_mp.min(mctx.progress.mp + heal_mp);
@@ -247,11 +253,16 @@ impl MainMenu {
                                 if mctx.progress.mp < mctx.progress.max_mp {
                                     mctx.audio.play_sfx(Sfx::Heal);
                                     mctx.progress.items[choice].amount -= 1;
+                                    mctx.progress.stats.items_used += 1;
                                     mctx.progress.mp = mctx.progress.max_mp;
                                 } else {
                                     mctx.audio.play_sfx(Sfx::Cancel);
                                 }
                             }
+                            // Ailments only last for the battle they're inflicted in.
+                            Item::Remedy => mctx.audio.play_sfx(Sfx::Cancel),
+                            // There's nothing to scan outside of battle.
+                            Item::Lens => mctx.audio.play_sfx(Sfx::Cancel),
                         }
                         self.update_hp_and_mp(mctx);
                         self.update_bottom_text_for_item_menu(mctx);
@@ -263,7 +274,7 @@ impl MainMenu {
             } else if mctx.input.is_key_pressed(GameKey::Up) {
                 mctx.audio.play_sfx(Sfx::Cursor);
                 if selection == 0 {
-                    selection = 4;
+                    selection = 6;
                 } else {
                     selection -= 1;
                 }
@@ -271,7 +282,7 @@ impl MainMenu {
                 self.update_bottom_line_for_item_menu(mctx, selection);
             } else if mctx.input.is_key_pressed(GameKey::Down) {
                 mctx.audio.play_sfx(Sfx::Cursor);
-                if selection == 4 {
+                if selection == 6 {
                     selection = 0;
                 } else {
                     selection += 1;
@@ -312,6 +323,7 @@ impl MainMenu {
                     {
                         mctx.audio.play_sfx(Sfx::Heal);
                         mctx.progress.mp -= magic_slot.magic.mp_cost();
+                        mctx.progress.stats.spells_cast += 1;
                         let heal_amount = (mctx.progress.max_hp + 1) / 2;
                         mctx.progress.hp = mctx.progress.max_hp.min(mctx.progress.hp + heal_amount);
                         self.update_hp_and_mp(mctx);
@@ -354,6 +366,23 @@ impl MainMenu {
         );
     }
 
+    async fn stats_page(&mut self, mctx: &mut ModeContext<'_, '_>) {
+        mctx.audio.play_sfx(Sfx::Confirm);
+
+        self.update_bottom_text_for_stats(mctx);
+
+        loop {
+            wait_once().await;
+
+            if mctx.input.is_key_pressed(GameKey::Cancel)
+                || mctx.input.is_key_pressed(GameKey::Confirm)
+            {
+                mctx.audio.play_sfx(Sfx::Cancel);
+                return;
+            }
+        }
+    }
+
     pub async fn update(&mut self, mctx: &mut ModeContext<'_, '_>) -> MainMenuEvent {
         self.update_hp_and_mp(mctx);
         self.update_bottom_text_for_status(mctx);
@@ -372,6 +401,13 @@ impl MainMenu {
                 }
                 return MainMenuEvent::Done;
             } else if mctx.input.is_key_pressed(GameKey::Confirm) {
+                // The status-only screen shows the stats page before closing.
+                if self.status_only && !self.stats_shown {
+                    mctx.audio.play_sfx(Sfx::Confirm);
+                    self.update_bottom_text_for_stats(mctx);
+                    self.stats_shown = true;
+                    continue;
+                }
                 match self.selection {
                     0 => {
                         mctx.audio.play_sfx(Sfx::Cancel);
@@ -382,7 +418,12 @@ impl MainMenu {
                     }
                     1 => self.magic_menu(mctx).await,
                     2 => self.item_menu(mctx).await,
-                    3 => {
+                    3 => self.stats_page(mctx).await,
+                    4 => {
+                        mctx.audio.play_sfx(Sfx::Confirm);
+                        return MainMenuEvent::Bestiary;
+                    }
+                    5 => {
                         mctx.audio.play_sfx(Sfx::Confirm);
                         self.bottom_text.set_text(mctx.gctx, mctx.res, "");
                         return MainMenuEvent::Options;
@@ -396,13 +437,13 @@ impl MainMenu {
                 if mctx.input.is_key_pressed(GameKey::Up) {
                     mctx.audio.play_sfx(Sfx::Cursor);
                     if self.selection == 0 {
-                        self.selection = 3;
+                        self.selection = 5;
                     } else {
                         self.selection -= 1;
                     }
                 } else if mctx.input.is_key_pressed(GameKey::Down) {
                     mctx.audio.play_sfx(Sfx::Cursor);
-                    if self.selection == 3 {
+                    if self.selection == 5 {
                         self.selection = 0;
                     } else {
                         self.selection += 1;
@@ -445,11 +486,13 @@ impl MainMenu {
             mctx.gctx,
             mctx.res,
             &format!(
-                " Back\n\n {:23.23}\n {:23.23}\n {:23.23}\n {:23.23}",
+                " Back\n\n {:23.23}\n {:23.23}\n {:23.23}\n {:23.23}\n {:23.23}\n {:23.23}",
                 mctx.progress.items[0].main_menu_entry(),
                 mctx.progress.items[1].main_menu_entry(),
                 mctx.progress.items[2].main_menu_entry(),
                 mctx.progress.items[3].main_menu_entry(),
+                mctx.progress.items[4].main_menu_entry(),
+                mctx.progress.items[5].main_menu_entry(),
             ),
         );
     }
@@ -475,8 +518,9 @@ impl MainMenu {
             &format!(
                 "    Weapon:{:>14}\
                \n    Attack:{:>14}\
-             \n\n     Armor:{:>14}\
+               \n     Armor:{:>14}\
                \n   Defense:{:>14}\
+               \nHit{:>4}% Crit{:>3}% Eva{:>3}%\
              \n\nExperience:{:>14}\
                \nNext Level:{:>14}",
                 mctx.progress
@@ -491,6 +535,9 @@ impl MainMenu {
                     .map(|a| a.name.as_str())
                     .unwrap_or("(none)"),
                 mctx.progress.defense,
+                mctx.progress.accuracy(),
+                mctx.progress.crit_chance(),
+                mctx.progress.evasion(),
                 mctx.progress.base_exp + mctx.progress.exp,
                 mctx.progress
                     .next_exp()
@@ -500,6 +547,33 @@ impl MainMenu {
         );
     }
 
+    fn update_bottom_text_for_stats(&mut self, mctx: &mut ModeContext) {
+        let stats = &mctx.progress.stats;
+        self.bottom_text.set_text(
+            mctx.gctx,
+            mctx.res,
+            &format!(
+                "Play Time{:>16}\
+             \n\nBattles{:>5} Won{:>9}\
+               \nFled{:>8} Lost{:>8}\
+               \nDealt{:>7} Taken{:>7}\
+               \nSpells{:>6} Items{:>7}\
+               \nDeaths{:>6} Steps{:>7}",
+                format_play_time(stats.play_time),
+                stats.battles_fought,
+                stats.battles_won,
+                stats.battles_fled,
+                stats.battles_lost,
+                stats.damage_dealt,
+                stats.damage_taken,
+                stats.spells_cast,
+                stats.items_used,
+                stats.deaths,
+                mctx.progress.steps.iter().sum::<i32>(),
+            ),
+        );
+    }
+
     fn update_hp_and_mp(&mut self, mctx: &mut ModeContext) {
         self.hp_text.set_text(
             mctx.gctx,
@@ -1,22 +1,26 @@
 mod battle;
+mod bestiary;
 mod credits;
 mod debug_menu;
 mod ending;
 mod intro;
 mod main_menu;
 mod options;
+mod slot_picker;
 mod text_box;
 mod title;
 mod walk_around;
 mod yes_no_prompt;
 
 pub use battle::*;
+pub use bestiary::*;
 pub use credits::*;
 pub use debug_menu::*;
 pub use ending::*;
 pub use intro::*;
 pub use main_menu::*;
 pub use options::*;
+pub use slot_picker::*;
 pub use text_box::*;
 pub use title::*;
 pub use walk_around::*;
@@ -52,12 +56,14 @@ macro_rules! impl_mode {
 
 pub enum Mode {
     Battle(Box<Battle>),
+    Bestiary(Box<Bestiary>),
     Credits(Box<Credits>),
     DebugMenu(Box<DebugMenu>),
     Ending(Box<Ending>),
     Intro(Box<Intro>),
     MainMenu(Box<MainMenu>),
     Options(Box<Options>),
+    SlotPicker(Box<SlotPicker>),
     TextBox(Box<TextBox>),
     Title(Box<Title>),
     WalkAround(Box<WalkAround>),
@@ -65,12 +71,14 @@ pub enum Mode {
 }
 
 impl_mode!(Battle, BattleEvent, update_battle_mode);
+impl_mode!(Bestiary, BestiaryEvent, update_bestiary_mode);
 impl_mode!(Credits, CreditsEvent, update_credits_mode);
 impl_mode!(DebugMenu, DebugMenuEvent, update_debug_menu_mode);
 impl_mode!(Ending, EndingEvent, update_ending_mode);
 impl_mode!(Intro, IntroEvent, update_intro_mode);
 impl_mode!(MainMenu, MainMenuEvent, update_main_menu_mode);
 impl_mode!(Options, OptionsEvent, update_options_mode);
+impl_mode!(SlotPicker, SlotPickerEvent, update_slot_picker_mode);
 impl_mode!(TextBox, TextBoxEvent, update_text_box_mode);
 impl_mode!(Title, TitleEvent, update_title_mode);
 impl_mode!(WalkAround, WalkAroundEvent, update_walk_around_mode);
@@ -84,12 +92,14 @@ impl Mode {
 
         match self {
             Battle(m) => m.draw(dctx),
+            Bestiary(m) => m.draw(dctx),
             Credits(m) => m.draw(dctx),
             DebugMenu(m) => m.draw(dctx),
             Ending(m) => m.draw(dctx),
             Intro(m) => m.draw(dctx),
             MainMenu(m) => m.draw(dctx),
             Options(m) => m.draw(dctx),
+            SlotPicker(m) => m.draw(dctx),
             TextBox(m) => m.draw(dctx),
             Title(m) => m.draw(dctx),
             WalkAround(m) => m.draw(dctx),
@@ -20,6 +20,8 @@ pub struct Options {
     music_meter: Meter,
     sound_text: Text,
     sound_meter: Meter,
+    text_speed_text: Text,
+    auto_advance_text: Text,
     credits_text: Text,
     quit_text: Text,
     options_changed: bool,
@@ -32,9 +34,9 @@ pub enum OptionsEvent {
 }
 
 #[cfg(target_arch = "wasm32")]
-const NUM_ENTRIES: i32 = 4;
+const NUM_ENTRIES: i32 = 6;
 #[cfg(not(target_arch = "wasm32"))]
-const NUM_ENTRIES: i32 = 5;
+const NUM_ENTRIES: i32 = 7;
 
 #[cfg(target_arch = "wasm32")]
 const QUIT_STR: &str = "";
@@ -76,8 +78,10 @@ impl Options {
                 [192, 192, 192],
                 MAX_SOUND_VOLUME as i32,
             ),
-            credits_text: Text::from_str(gctx, res, base_x + 6, base_y + 4 * 8, "Credits"),
-            quit_text: Text::from_str(gctx, res, base_x + 6, base_y + 5 * 8, QUIT_STR),
+            text_speed_text: Text::new(res, base_x + 6, base_y + 4 * 8),
+            auto_advance_text: Text::new(res, base_x + 6, base_y + 5 * 8),
+            credits_text: Text::from_str(gctx, res, base_x + 6, base_y + 6 * 8, "Credits"),
+            quit_text: Text::from_str(gctx, res, base_x + 6, base_y + 7 * 8, QUIT_STR),
             options_changed: false,
         }
     }
@@ -89,6 +93,8 @@ impl Options {
         self.music_meter.draw(dctx.gctx);
         self.sound_text.draw(dctx.gctx);
         self.sound_meter.draw(dctx.gctx);
+        self.text_speed_text.draw(dctx.gctx);
+        self.auto_advance_text.draw(dctx.gctx);
         self.credits_text.draw(dctx.gctx);
         self.quit_text.draw(dctx.gctx);
     }
@@ -98,6 +104,7 @@ impl Options {
             let opts = SavedOptions {
                 music_volume: mctx.audio.get_music_volume_custom(),
                 sound_volume: mctx.audio.get_sound_volume_custom(),
+                battle_speed: Some(*mctx.battle_speed),
             };
             // Save options on a best-effort basis.
             let _ = opts.save();
@@ -107,6 +114,7 @@ impl Options {
 
     pub async fn update(&mut self, mctx: &mut ModeContext<'_, '_>) -> OptionsEvent {
         self.update_volumes(mctx);
+        self.update_battle_speed(mctx);
         self.update_cursor_pos();
 
         loop {
@@ -129,8 +137,9 @@ impl Options {
                         }
                     }
                     2 => mctx.audio.play_sfx(Sfx::Confirm),
-                    3 => return OptionsEvent::Credits,
-                    4 => {
+                    3 | 4 => {}
+                    5 => return OptionsEvent::Credits,
+                    6 => {
                         self.save_changed_options(mctx);
                         return OptionsEvent::Quit;
                     }
@@ -199,10 +208,52 @@ impl Options {
                     self.update_volumes(mctx);
                     mctx.audio.play_sfx(Sfx::Confirm);
                 }
+            } else if self.selection == 3 || self.selection == 4 {
+                let old_battle_speed = *mctx.battle_speed;
+                let battle_speed = &mut *mctx.battle_speed;
+
+                if mctx.input.is_key_pressed(GameKey::Left) {
+                    if self.selection == 3 {
+                        battle_speed.text_speed = battle_speed.text_speed.slower();
+                    } else {
+                        battle_speed.auto_advance = battle_speed.auto_advance.saturating_sub(1);
+                    }
+                } else if mctx.input.is_key_pressed(GameKey::Right) {
+                    if self.selection == 3 {
+                        battle_speed.text_speed = battle_speed.text_speed.faster();
+                    } else {
+                        battle_speed.auto_advance =
+                            (battle_speed.auto_advance + 1).min(MAX_AUTO_ADVANCE);
+                    }
+                }
+
+                if *battle_speed != old_battle_speed {
+                    self.options_changed = true;
+                    self.update_battle_speed(mctx);
+                    mctx.audio.play_sfx(Sfx::Cursor);
+                }
             }
         }
     }
 
+    fn update_battle_speed(&mut self, mctx: &mut ModeContext) {
+        self.text_speed_text.set_text(
+            mctx.gctx,
+            mctx.res,
+            &format!("Battle Text{:>9}", mctx.battle_speed.text_speed.name()),
+        );
+
+        let auto_advance = match mctx.battle_speed.auto_advance {
+            0 => String::from("Off"),
+            seconds => format!("{seconds} sec"),
+        };
+        self.auto_advance_text.set_text(
+            mctx.gctx,
+            mctx.res,
+            &format!("Auto-Advance{:>8}", auto_advance),
+        );
+    }
+
     fn update_cursor_pos(&mut self) {
         let y = if self.selection == 0 {
             self.base_y
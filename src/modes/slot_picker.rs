@@ -0,0 +1,129 @@
+use crate::async_utils::wait_once;
+use crate::audio::*;
+use crate::contexts::*;
+use crate::input::*;
+use crate::progress::*;
+use crate::resources::*;
+use crate::text::*;
+use crate::window::*;
+
+use miniquad::GlContext;
+
+pub struct SlotPicker {
+    window: Window,
+    text: Text,
+    cursor: Text,
+    slot_used: [bool; NUM_SAVE_SLOTS],
+    allow_empty: bool,
+    selection: i32,
+}
+
+pub enum SlotPickerEvent {
+    Cancel,
+    Slot(usize),
+}
+
+const WINDOW_X: i32 = 44;
+const WINDOW_Y: i32 = 40;
+const TEXT_X: i32 = WINDOW_X + 14;
+const TEXT_Y: i32 = WINDOW_Y + 8;
+const CURSOR_X: i32 = WINDOW_X + 8;
+const CURSOR_Y: i32 = TEXT_Y;
+
+impl SlotPicker {
+    pub fn new(gctx: &mut GlContext, res: &Resources, title: &str, allow_empty: bool) -> Self {
+        let mut slot_used = [false; NUM_SAVE_SLOTS];
+        let mut s = format!("Back{title:>30}\n");
+        for (slot, used) in slot_used.iter_mut().enumerate() {
+            s.push('\n');
+            match SaveSlotSummary::load(slot) {
+                Some(summary) => {
+                    *used = true;
+                    s.push_str(&format!(
+                        "Slot {}   Level {:<3} {:>12}\n         {:16}{:>9}\n",
+                        slot + 1,
+                        summary.level,
+                        player_rank(summary.level),
+                        summary.location,
+                        format_play_time(summary.play_time),
+                    ));
+                }
+                None => s.push_str(&format!("Slot {}   (empty)\n\n", slot + 1)),
+            }
+        }
+
+        Self {
+            window: Window::new(
+                gctx,
+                res,
+                WINDOW_X,
+                WINDOW_Y,
+                232,
+                (2 + 3 * NUM_SAVE_SLOTS as i32) * 8 + 8,
+            ),
+            text: Text::from_str(gctx, res, TEXT_X, TEXT_Y, &s),
+            cursor: Text::from_str(gctx, res, CURSOR_X, CURSOR_Y, "►"),
+            slot_used,
+            allow_empty,
+            selection: 0,
+        }
+    }
+
+    pub fn draw(&self, dctx: &mut DrawContext) {
+        self.window.draw(dctx.gctx);
+        self.text.draw(dctx.gctx);
+        self.cursor.draw(dctx.gctx);
+    }
+
+    pub async fn update(&mut self, mctx: &mut ModeContext<'_, '_>) -> SlotPickerEvent {
+        const NUM_ENTRIES: i32 = NUM_SAVE_SLOTS as i32 + 1;
+
+        self.update_cursor_pos();
+
+        loop {
+            wait_once().await;
+
+            if mctx.input.is_key_pressed(GameKey::Cancel) {
+                mctx.audio.play_sfx(Sfx::Cancel);
+                return SlotPickerEvent::Cancel;
+            } else if mctx.input.is_key_pressed(GameKey::Confirm) {
+                if self.selection == 0 {
+                    mctx.audio.play_sfx(Sfx::Cancel);
+                    return SlotPickerEvent::Cancel;
+                }
+                let slot = usize::try_from(self.selection - 1).expect("selection - 1 as usize");
+                if self.allow_empty || self.slot_used[slot] {
+                    mctx.audio.play_sfx(Sfx::Confirm);
+                    return SlotPickerEvent::Slot(slot);
+                } else {
+                    mctx.audio.play_sfx(Sfx::Cancel);
+                }
+            } else if mctx.input.is_key_pressed(GameKey::Up) {
+                mctx.audio.play_sfx(Sfx::Cursor);
+                if self.selection == 0 {
+                    self.selection = NUM_ENTRIES - 1;
+                } else {
+                    self.selection -= 1;
+                }
+                self.update_cursor_pos();
+            } else if mctx.input.is_key_pressed(GameKey::Down) {
+                mctx.audio.play_sfx(Sfx::Cursor);
+                if self.selection == NUM_ENTRIES - 1 {
+                    self.selection = 0;
+                } else {
+                    self.selection += 1;
+                }
+                self.update_cursor_pos();
+            }
+        }
+    }
+
+    fn update_cursor_pos(&mut self) {
+        let y = if self.selection == 0 {
+            CURSOR_Y
+        } else {
+            CURSOR_Y + (2 + 3 * (self.selection - 1)) * 8
+        };
+        self.cursor.set_offset(CURSOR_X, y);
+    }
+}
@@ -36,7 +36,7 @@ const CURSOR_Y: i32 = MENU_Y;
 
 impl Title {
     pub fn new(gctx: &mut GlContext, res: &Resources) -> Self {
-        let can_continue = save_data_exists();
+        let can_continue = any_save_data_exists();
 
         Self {
             title: Sprite::new(gctx, res, "title.png"),
@@ -16,11 +16,13 @@
 // 1. [here] In-game progress structs and enums are converted to the most recent storage-stable
 //    version.
 // 2. [version submodule] The storage-stable data is converted into a string (JSON).
-// 3. [here] The string is written to stable storage, along with a header and version number.
+// 3. [here] The string is written to stable storage, along with a header, version number and
+//    checksum.  The previous save data, if valid, is kept as a backup.
 //
 // When loading:
 //
-// 1. [here] The save data header is split into a version number and unparsed data.
+// 1. [here] The save data header is split into a version number, checksum and unparsed data, and
+//    the checksum is verified if present.
 // 2. [version submodule] Loading code checks the version number before dealing with the data.
 // 3. [version submodule] If the version matches, parse the data and return it.
 // 4. [version submodule] If the version is older, send it to the loading code in the submodule for
@@ -36,7 +38,7 @@
 // 3.  [here] Replace `use v1::*` with `use v2::*`.
 // 4.  [here] Change `SAVE_VERSION` to `2`.
 // 5.  [src/progress/] Create a new submodule file: `src/progress/v2.rs`.
-// 6.  [v2.rs] Add `use super::v1::*;`.
+// 6.  [v2.rs] Add `pub use super::v1::*;` so reused older structs and enums stay visible here.
 // 7.  [v2.rs] Add `use miniserde::{Deserialize, Serialize};`.
 // 8.  [v2.rs] Copy-and-paste the current in-game progress structs and enums that have changed,
 //     suffix them with "V2" and add `Serialize` and `Deserialize` derives to them.  Reuse "V1"
@@ -63,22 +65,45 @@
 //   deal with internal save format breakage manually.
 
 mod v1;
+mod v2;
+mod v3;
+mod v4;
+mod v5;
+mod v6;
+mod v7;
+mod v8;
+mod v9;
 
-use v1::*;
+use v9::*;
 
+use crate::direction::*;
 use crate::enemy::*;
+use crate::storage::*;
+
+use miniserde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
 
 pub struct Armor {
     pub name: String,
     pub defense: i32,
 }
 
+#[derive(Default)]
+pub struct BestiaryEntry {
+    pub seen: u32,
+    pub defeated: u32,
+    // Elements whose affinity Coric has learned, by casting them or with a Lens.
+    pub known_affinities: BTreeSet<Magic>,
+}
+
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum Item {
     Salve,
     XSalve,
     Tonic,
     XTonic,
+    Remedy,
+    Lens,
 }
 
 pub struct ItemSlot {
@@ -86,7 +111,7 @@ pub struct ItemSlot {
     pub amount: i32,
 }
 
-#[derive(Clone, Copy, Eq, PartialEq)]
+#[derive(Clone, Copy, Eq, Ord, PartialEq, PartialOrd)]
 pub enum Magic {
     Heal,
     EarthEdge,
@@ -94,6 +119,13 @@ pub enum Magic {
     FireEdge,
 }
 
+pub struct Location {
+    pub level_id: String,
+    pub x: i32,
+    pub y: i32,
+    pub dir: Direction,
+}
+
 pub struct MagicSlot {
     pub magic: Magic,
     pub known: bool,
@@ -121,9 +153,34 @@ pub struct Progress {
     pub collected_chests: Vec<String>,
     pub turned_levers: Vec<String>,
     pub steps: Vec<i32>,
-    pub earth_defeated: bool,
-    pub water_defeated: bool,
-    pub fire_defeated: bool,
+    pub location: Location,
+    pub flags: BTreeSet<String>,
+    pub vars: BTreeMap<String, i32>,
+    pub stats: Stats,
+    pub bestiary: BTreeMap<String, BestiaryEntry>,
+}
+
+// Stored next to each save, so the slot picker can describe it without loading the save itself.
+#[derive(Deserialize, Serialize)]
+pub struct SaveSlotSummary {
+    pub level: i32,
+    pub location: String,
+    // Copied from stats, which are the source of truth once loaded.
+    pub play_time: u32,
+}
+
+#[derive(Default)]
+pub struct Stats {
+    pub play_time: u32,
+    pub battles_fought: u32,
+    pub battles_won: u32,
+    pub battles_fled: u32,
+    pub battles_lost: u32,
+    pub damage_dealt: u32,
+    pub damage_taken: u32,
+    pub spells_cast: u32,
+    pub items_used: u32,
+    pub deaths: u32,
 }
 
 #[rustfmt::skip]
@@ -133,9 +190,53 @@ const EXP_FOR_NEXT_LEVEL: [i32; 29] = [
     9575, 13080, 13235, 13380, 16000, 19250, 23000, 28000, 33300,
 ];
 
-const SAVE_KEY: &str = "save";
+pub const EARTH_DEFEATED: &str = "earth_defeated";
+pub const WATER_DEFEATED: &str = "water_defeated";
+pub const FIRE_DEFEATED: &str = "fire_defeated";
+
+// Every story flag and variable used by scripts, so they can be listed in the debug menu.
+pub const STORY_FLAGS: &[&str] = &[EARTH_DEFEATED, WATER_DEFEATED, FIRE_DEFEATED];
+pub const STORY_VARS: &[&str] = &[];
+
+pub const MAX_ITEM_AMOUNT: i32 = 9;
+
+// Chances out of 100 in battle, before equipment bonuses.
+pub const BASE_ACCURACY: i32 = 100;
+pub const BASE_CRIT_CHANCE: i32 = 4;
+pub const BASE_EVASION: i32 = 4;
+
+pub const NUM_SAVE_SLOTS: usize = 3;
+
+const NUM_SAVE_BACKUPS: usize = 3;
+
+pub const SAVE_KEY: &str = "save";
+pub const SAVE_SUMMARY_KEY: &str = "save_summary";
 const SAVE_HEADER_START: &str = "// cqsave ";
-const SAVE_VERSION: u32 = 1;
+pub const SAVE_VERSION: u32 = 9;
+
+impl Armor {
+    // Equipment bonuses are looked up by name instead of being saved, so older saves get them too.
+    pub fn evasion_bonus(&self) -> i32 {
+        match self.name.as_str() {
+            "Chain Vest" => 2,
+            "Steel Armor" => -2,
+            "Mythic Plate" => 6,
+            _ => 0,
+        }
+    }
+
+    // Like evasion, armor that's easier to move in makes it easier to catch enemies off guard, and
+    // harder for them to do the same, so Steel Armor hinders it and Mythic Plate helps it most.
+    pub fn initiative_bonus(&self) -> i32 {
+        match self.name.as_str() {
+            "Leather Armor" => 2,
+            "Chain Vest" => 4,
+            "Steel Armor" => -4,
+            "Mythic Plate" => 6,
+            _ => 0,
+        }
+    }
+}
 
 impl From<&Armor> for ArmorV1 {
     fn from(s: &Armor) -> Self {
@@ -155,13 +256,70 @@ impl From<ArmorV1> for Armor {
     }
 }
 
+impl From<&BestiaryEntry> for BestiaryEntryV9 {
+    fn from(s: &BestiaryEntry) -> Self {
+        Self {
+            seen: s.seen,
+            defeated: s.defeated,
+            known_affinities: s
+                .known_affinities
+                .iter()
+                .map(|&m| MagicV1::from(m))
+                .collect(),
+        }
+    }
+}
+
+impl From<BestiaryEntryV9> for BestiaryEntry {
+    fn from(l: BestiaryEntryV9) -> Self {
+        Self {
+            seen: l.seen,
+            defeated: l.defeated,
+            known_affinities: l.known_affinities.into_iter().map(Magic::from).collect(),
+        }
+    }
+}
+
+impl From<Direction> for DirectionV2 {
+    fn from(s: Direction) -> Self {
+        match s {
+            Direction::North => Self::North,
+            Direction::East => Self::East,
+            Direction::South => Self::South,
+            Direction::West => Self::West,
+        }
+    }
+}
+
+impl From<DirectionV2> for Direction {
+    fn from(l: DirectionV2) -> Self {
+        match l {
+            DirectionV2::North => Self::North,
+            DirectionV2::East => Self::East,
+            DirectionV2::South => Self::South,
+            DirectionV2::West => Self::West,
+        }
+    }
+}
+
 impl Item {
+    pub const ALL: [Self; 6] = [
+        Self::Salve,
+        Self::XSalve,
+        Self::Tonic,
+        Self::XTonic,
+        Self::Remedy,
+        Self::Lens,
+    ];
+
     fn description(self) -> &'static str {
         match self {
             Self::Salve => "Heals 30% of max HP.",
             Self::XSalve => "Heals all HP.",
             Self::Tonic => "Restores 30% of max MP.",
             Self::XTonic => "Restores all MP.",
+            Self::Remedy => "Cures ailments in battle.",
+            Self::Lens => "Reveals an enemy's stats.",
         }
     }
 
@@ -171,28 +329,45 @@ impl Item {
             Self::XSalve => "XSalve",
             Self::Tonic => "Tonic",
             Self::XTonic => "XTonic",
+            Self::Remedy => "Remedy",
+            Self::Lens => "Lens",
+        }
+    }
+
+    pub fn plural_name(self) -> &'static str {
+        match self {
+            Self::Salve => "Salves",
+            Self::XSalve => "XSalves",
+            Self::Tonic => "Tonics",
+            Self::XTonic => "XTonics",
+            Self::Remedy => "Remedies",
+            Self::Lens => "Lenses",
         }
     }
 }
 
-impl From<Item> for ItemV1 {
+impl From<Item> for ItemV8 {
     fn from(s: Item) -> Self {
         match s {
             Item::Salve => Self::Salve,
             Item::XSalve => Self::XSalve,
             Item::Tonic => Self::Tonic,
             Item::XTonic => Self::XTonic,
+            Item::Remedy => Self::Remedy,
+            Item::Lens => Self::Lens,
         }
     }
 }
 
-impl From<ItemV1> for Item {
-    fn from(l: ItemV1) -> Self {
+impl From<ItemV8> for Item {
+    fn from(l: ItemV8) -> Self {
         match l {
-            ItemV1::Salve => Self::Salve,
-            ItemV1::XSalve => Self::XSalve,
-            ItemV1::Tonic => Self::Tonic,
-            ItemV1::XTonic => Self::XTonic,
+            ItemV8::Salve => Self::Salve,
+            ItemV8::XSalve => Self::XSalve,
+            ItemV8::Tonic => Self::Tonic,
+            ItemV8::XTonic => Self::XTonic,
+            ItemV8::Remedy => Self::Remedy,
+            ItemV8::Lens => Self::Lens,
         }
     }
 }
@@ -208,7 +383,7 @@ impl ItemSlot {
 
     pub fn battle_menu_entry(&self) -> String {
         if self.amount > 0 {
-            format!("{:9.9}{:2}", self.item.name(), self.amount)
+            format!("{:6.6}{:2}", self.item.name(), self.amount)
         } else {
             String::new()
         }
@@ -223,7 +398,7 @@ impl ItemSlot {
     }
 }
 
-impl From<&ItemSlot> for ItemSlotV1 {
+impl From<&ItemSlot> for ItemSlotV8 {
     fn from(s: &ItemSlot) -> Self {
         Self {
             item: s.item.into(),
@@ -232,8 +407,8 @@ impl From<&ItemSlot> for ItemSlotV1 {
     }
 }
 
-impl From<ItemSlotV1> for ItemSlot {
-    fn from(l: ItemSlotV1) -> Self {
+impl From<ItemSlotV8> for ItemSlot {
+    fn from(l: ItemSlotV8) -> Self {
         Self {
             item: l.item.into(),
             amount: l.amount,
@@ -242,6 +417,8 @@ impl From<ItemSlotV1> for ItemSlot {
 }
 
 impl Magic {
+    pub const EDGES: [Self; 3] = [Self::EarthEdge, Self::WaterEdge, Self::FireEdge];
+
     fn description(self) -> &'static str {
         match self {
             Self::Heal => "Heals 50% of max HP.",
@@ -270,6 +447,28 @@ impl Magic {
     }
 }
 
+impl From<&Location> for LocationV2 {
+    fn from(s: &Location) -> Self {
+        Self {
+            level_id: s.level_id.clone(),
+            x: s.x,
+            y: s.y,
+            dir: DirectionV2::from(s.dir),
+        }
+    }
+}
+
+impl From<LocationV2> for Location {
+    fn from(l: LocationV2) -> Self {
+        Self {
+            level_id: l.level_id,
+            x: l.x,
+            y: l.y,
+            dir: Direction::from(l.dir),
+        }
+    }
+}
+
 impl From<Magic> for MagicV1 {
     fn from(s: Magic) -> Self {
         match s {
@@ -336,6 +535,25 @@ impl From<MagicSlotV1> for MagicSlot {
     }
 }
 
+impl Weapon {
+    pub fn accuracy_bonus(&self) -> i32 {
+        match self.name.as_str() {
+            "Duelist Sword" => 5,
+            "Valor Blade" => 5,
+            _ => 0,
+        }
+    }
+
+    pub fn crit_bonus(&self) -> i32 {
+        match self.name.as_str() {
+            "Long Sword" => 2,
+            "Duelist Sword" => 6,
+            "Valor Blade" => 4,
+            _ => 0,
+        }
+    }
+}
+
 impl From<&Weapon> for WeaponV1 {
     fn from(s: &Weapon) -> Self {
         Self {
@@ -385,6 +603,14 @@ impl Progress {
                     item: Item::XTonic,
                     amount: 0,
                 },
+                ItemSlot {
+                    item: Item::Remedy,
+                    amount: 0,
+                },
+                ItemSlot {
+                    item: Item::Lens,
+                    amount: 0,
+                },
             ],
             magic: vec![
                 MagicSlot {
@@ -407,33 +633,118 @@ impl Progress {
             collected_chests: Vec::new(),
             turned_levers: Vec::new(),
             steps: vec![0; EncounterGroup::NUM_GROUPS + 1],
-            earth_defeated: false,
-            water_defeated: false,
-            fire_defeated: false,
+            location: Location {
+                level_id: String::from("Start"),
+                x: 6,
+                y: 3,
+                dir: Direction::South,
+            },
+            flags: BTreeSet::new(),
+            vars: BTreeMap::new(),
+            stats: Stats::default(),
+            bestiary: BTreeMap::new(),
         }
     }
 
-    pub fn load() -> Result<Self, &'static str> {
-        let raw_data = quad_storage::STORAGE
+    pub fn load(slot: usize) -> Result<Self, StorageError> {
+        let storage = quad_storage::STORAGE
             .lock()
-            .map_err(|_| "storage error")?
-            .get(SAVE_KEY)
-            .ok_or("no save file found")?;
-        let version_and_data = raw_data
+            .map_err(|_| StorageError::Unavailable)?;
+        let raw_data = storage
+            .get(&slot_key(SAVE_KEY, slot))
+            .ok_or(StorageError::Missing)?;
+        let raw_summary = storage.get(&slot_key(SAVE_SUMMARY_KEY, slot));
+        Self::from_slot_data(&raw_data, raw_summary.as_deref())
+    }
+
+    pub fn from_save_data(raw_data: &str) -> Result<Self, StorageError> {
+        let (header, data) = raw_data.split_once('\n').ok_or(StorageError::BadHeader)?;
+        let version_and_checksum = header
             .strip_prefix(SAVE_HEADER_START)
-            .ok_or("invalid save data")?;
-        let (version_str, data) = version_and_data
-            .split_once(|c: char| !c.is_ascii_digit())
-            .ok_or("missing version number")?;
+            .ok_or(StorageError::BadHeader)?;
+        // Saves from before checksums were added have only a version number in the header.
+        let (version_str, checksum_str) = match version_and_checksum.split_once(' ') {
+            Some((version_str, checksum_str)) => (version_str, Some(checksum_str)),
+            None => (version_and_checksum, None),
+        };
         let version = version_str
             .parse::<u32>()
-            .map_err(|_| "failed to parse version")?;
-        Ok(Progress::from(ProgressV1::from_str(
+            .map_err(|_| StorageError::BadHeader)?;
+        if let Some(checksum_str) = checksum_str {
+            let expected =
+                u32::from_str_radix(checksum_str, 16).map_err(|_| StorageError::BadHeader)?;
+            if checksum(data) != expected {
+                return Err(StorageError::BadChecksum);
+            }
+        }
+        Ok(Progress::from(ProgressV9::from_str(
             data.trim_start(),
             version,
         )?))
     }
 
+    // Like `from_save_data`, but also takes the slot summary that was saved alongside it, if any.
+    pub fn from_slot_data(raw_data: &str, raw_summary: Option<&str>) -> Result<Self, StorageError> {
+        let mut progress = Self::from_save_data(raw_data)?;
+        // Saves from before stats existed kept play time only in their slot summary.
+        if progress.stats.play_time == 0 {
+            if let Some(summary) =
+                raw_summary.and_then(|s| miniserde::json::from_str::<SaveSlotSummary>(s).ok())
+            {
+                progress.stats.play_time = summary.play_time;
+            }
+        }
+        Ok(progress)
+    }
+
+    pub fn accuracy(&self) -> i32 {
+        BASE_ACCURACY
+            + self
+                .weapon
+                .as_ref()
+                .map(Weapon::accuracy_bonus)
+                .unwrap_or(0)
+    }
+
+    // Adds as many of the items as there's room for, returning how many that was.
+    pub fn add_items(&mut self, item: Item, amount: i32) -> i32 {
+        let item_slot = match self.items.iter_mut().find(|s| s.item == item) {
+            Some(s) => s,
+            None => panic!("progress item slot for {:?}", item),
+        };
+        let added = amount.min(MAX_ITEM_AMOUNT - item_slot.amount).max(0);
+        item_slot.amount += added;
+        added
+    }
+
+    pub fn bestiary_entry_mut(&mut self, enemy_name: &str) -> &mut BestiaryEntry {
+        self.bestiary.entry(String::from(enemy_name)).or_default()
+    }
+
+    pub fn crit_chance(&self) -> i32 {
+        BASE_CRIT_CHANCE + self.weapon.as_ref().map(Weapon::crit_bonus).unwrap_or(0)
+    }
+
+    pub fn evasion(&self) -> i32 {
+        BASE_EVASION + self.armor.as_ref().map(Armor::evasion_bonus).unwrap_or(0)
+    }
+
+    pub fn flag(&self, name: &str) -> bool {
+        self.flags.contains(name)
+    }
+
+    // Known flags first, then any others found in save data.
+    pub fn flag_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = STORY_FLAGS.iter().map(|&name| String::from(name)).collect();
+        names.extend(
+            self.flags
+                .iter()
+                .filter(|name| !STORY_FLAGS.contains(&name.as_str()))
+                .cloned(),
+        );
+        names
+    }
+
     pub fn gain_level(&mut self) {
         if let Some(next_exp) = self.next_exp() {
             self.base_exp += next_exp;
@@ -459,6 +770,14 @@ impl Progress {
         true
     }
 
+    // Odds of catching enemies off guard, on top of those from Coric's level.
+    pub fn initiative(&self) -> i32 {
+        self.armor
+            .as_ref()
+            .map(Armor::initiative_bonus)
+            .unwrap_or(0)
+    }
+
     pub fn maybe_give_items(&mut self, item: Item, min_amount: i32) -> i32 {
         let item_slot = match self.items.iter_mut().find(|s| s.item == item) {
             Some(s) => s,
@@ -509,20 +828,215 @@ impl Progress {
         EXP_FOR_NEXT_LEVEL.get(index).copied()
     }
 
-    pub fn save(&self) -> Result<(), &'static str> {
-        let raw_data = format!(
-            "{SAVE_HEADER_START}{SAVE_VERSION}\n{}",
-            &ProgressV1::from(self).to_string()
-        );
-        quad_storage::STORAGE
+    // Check loaded progress for values that would break the game later, fixing what can be fixed.
+    // Returns a description of each repair made.
+    pub fn repair(&mut self) -> Result<Vec<String>, StorageError> {
+        let mut repairs = Vec::new();
+
+        if self.max_hp < 1 {
+            return Err(StorageError::InvalidValue(format!(
+                "max_hp is {}",
+                self.max_hp
+            )));
+        }
+        if self.max_mp < 0 {
+            return Err(StorageError::InvalidValue(format!(
+                "max_mp is {}",
+                self.max_mp
+            )));
+        }
+        if self.level < 1 {
+            repairs.push(format!("level {} raised to 1", self.level));
+            self.level = 1;
+        }
+        if !(1..=self.max_hp).contains(&self.hp) {
+            let hp = self.hp.clamp(1, self.max_hp);
+            repairs.push(format!("hp {} set to {hp}", self.hp));
+            self.hp = hp;
+        }
+        if !(0..=self.max_mp).contains(&self.mp) {
+            let mp = self.mp.clamp(0, self.max_mp);
+            repairs.push(format!("mp {} set to {mp}", self.mp));
+            self.mp = mp;
+        }
+        if self.exp < 0 {
+            repairs.push(format!("exp {} raised to 0", self.exp));
+            self.exp = 0;
+        }
+        if self.attack < 0 {
+            repairs.push(format!("attack {} raised to 0", self.attack));
+            self.attack = 0;
+        }
+        if self.defense < 0 {
+            repairs.push(format!("defense {} raised to 0", self.defense));
+            self.defense = 0;
+        }
+
+        // Rebuild item and magic slots in their usual order, with exactly one slot each.
+        let defaults = Progress::new();
+        let mut items = Vec::with_capacity(defaults.items.len());
+        for default_slot in defaults.items {
+            let item = default_slot.item;
+            let mut amounts = self
+                .items
+                .iter()
+                .filter(|s| s.item == item)
+                .map(|s| s.amount);
+            let amount = match amounts.next() {
+                Some(amount) => amount,
+                None => {
+                    repairs.push(format!("missing {} slot added", item.name()));
+                    0
+                }
+            };
+            if amounts.next().is_some() {
+                repairs.push(format!("duplicate {} slots removed", item.name()));
+            }
+            let clamped_amount = amount.clamp(0, MAX_ITEM_AMOUNT);
+            if clamped_amount != amount {
+                repairs.push(format!(
+                    "{} amount {amount} set to {clamped_amount}",
+                    item.name()
+                ));
+            }
+            items.push(ItemSlot {
+                item,
+                amount: clamped_amount,
+            });
+        }
+        self.items = items;
+
+        let mut magic = Vec::with_capacity(defaults.magic.len());
+        for default_slot in defaults.magic {
+            let m = default_slot.magic;
+            let mut knowns = self.magic.iter().filter(|s| s.magic == m).map(|s| s.known);
+            let known = match knowns.next() {
+                Some(known) => known,
+                None => {
+                    repairs.push(format!("missing {} slot added", m.name()));
+                    false
+                }
+            };
+            if knowns.next().is_some() {
+                repairs.push(format!("duplicate {} slots removed", m.name()));
+            }
+            magic.push(MagicSlot { magic: m, known });
+        }
+        self.magic = magic;
+
+        if self.steps.len() != defaults.steps.len() {
+            repairs.push(format!(
+                "steps length {} set to {}",
+                self.steps.len(),
+                defaults.steps.len()
+            ));
+            self.steps.resize(defaults.steps.len(), 0);
+        }
+        for steps in self.steps.iter_mut().filter(|steps| **steps < 0) {
+            repairs.push(format!("steps {steps} raised to 0"));
+            *steps = 0;
+        }
+
+        Ok(repairs)
+    }
+
+    pub fn restore_backup(slot: usize) -> Result<Self, StorageError> {
+        let mut storage = quad_storage::STORAGE
+            .lock()
+            .map_err(|_| StorageError::Unavailable)?;
+        for backup in 1..=NUM_SAVE_BACKUPS {
+            let Some(raw_data) = storage.get(&backup_key(SAVE_KEY, slot, backup)) else {
+                continue;
+            };
+            let raw_summary = storage.get(&backup_key(SAVE_SUMMARY_KEY, slot, backup));
+            let Ok(progress) = Self::from_slot_data(&raw_data, raw_summary.as_deref()) else {
+                continue;
+            };
+            storage.set(&slot_key(SAVE_KEY, slot), &raw_data);
+            match raw_summary {
+                Some(raw_summary) => storage.set(&slot_key(SAVE_SUMMARY_KEY, slot), &raw_summary),
+                None => storage.remove(&slot_key(SAVE_SUMMARY_KEY, slot)),
+            }
+            return Ok(progress);
+        }
+        Err(StorageError::Missing)
+    }
+
+    pub fn save(&self, slot: usize, summary: &SaveSlotSummary) -> Result<(), StorageError> {
+        let raw_data = self.to_save_data();
+        let mut storage = quad_storage::STORAGE
             .lock()
-            .map_err(|_| "storage error")?
-            .set(SAVE_KEY, &raw_data);
+            .map_err(|_| StorageError::Unavailable)?;
+
+        // Keep the previous save as a backup, but only if it's valid.
+        if let Some(old_raw_data) = storage.get(&slot_key(SAVE_KEY, slot)) {
+            if Self::from_save_data(&old_raw_data).is_ok() {
+                for backup in (1..NUM_SAVE_BACKUPS).rev() {
+                    for base_key in [SAVE_KEY, SAVE_SUMMARY_KEY] {
+                        match storage.get(&backup_key(base_key, slot, backup)) {
+                            Some(value) => {
+                                storage.set(&backup_key(base_key, slot, backup + 1), &value)
+                            }
+                            None => storage.remove(&backup_key(base_key, slot, backup + 1)),
+                        }
+                    }
+                }
+                storage.set(&backup_key(SAVE_KEY, slot, 1), &old_raw_data);
+                match storage.get(&slot_key(SAVE_SUMMARY_KEY, slot)) {
+                    Some(old_summary) => {
+                        storage.set(&backup_key(SAVE_SUMMARY_KEY, slot, 1), &old_summary)
+                    }
+                    None => storage.remove(&backup_key(SAVE_SUMMARY_KEY, slot, 1)),
+                }
+            }
+        }
+
+        storage.set(&slot_key(SAVE_KEY, slot), &raw_data);
+        storage.set(
+            &slot_key(SAVE_SUMMARY_KEY, slot),
+            &miniserde::json::to_string(summary),
+        );
         Ok(())
     }
+
+    pub fn set_flag(&mut self, name: &str, value: bool) {
+        if value {
+            self.flags.insert(String::from(name));
+        } else {
+            self.flags.remove(name);
+        }
+    }
+
+    pub fn set_var(&mut self, name: &str, value: i32) {
+        self.vars.insert(String::from(name), value);
+    }
+
+    pub fn to_save_data(&self) -> String {
+        let data = ProgressV9::from(self).to_string();
+        format!(
+            "{SAVE_HEADER_START}{SAVE_VERSION} {:08x}\n{data}",
+            checksum(&data)
+        )
+    }
+
+    pub fn var(&self, name: &str) -> i32 {
+        self.vars.get(name).copied().unwrap_or(0)
+    }
+
+    // Known variables first, then any others found in save data.
+    pub fn var_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = STORY_VARS.iter().map(|&name| String::from(name)).collect();
+        names.extend(
+            self.vars
+                .keys()
+                .filter(|name| !STORY_VARS.contains(&name.as_str()))
+                .cloned(),
+        );
+        names
+    }
 }
 
-impl From<&Progress> for ProgressV1 {
+impl From<&Progress> for ProgressV9 {
     fn from(s: &Progress) -> Self {
         Self {
             hp: s.hp,
@@ -536,20 +1050,26 @@ impl From<&Progress> for ProgressV1 {
             base_exp: s.base_exp,
             weapon: s.weapon.as_ref().map(WeaponV1::from),
             armor: s.armor.as_ref().map(ArmorV1::from),
-            items: s.items.iter().map(ItemSlotV1::from).collect(),
+            items: s.items.iter().map(ItemSlotV8::from).collect(),
             magic: s.magic.iter().map(MagicSlotV1::from).collect(),
             collected_chests: s.collected_chests.clone(),
             turned_levers: s.turned_levers.clone(),
             steps: s.steps.clone(),
-            earth_defeated: s.earth_defeated,
-            water_defeated: s.water_defeated,
-            fire_defeated: s.fire_defeated,
+            location: LocationV2::from(&s.location),
+            flags: s.flags.iter().cloned().collect(),
+            vars: s.vars.clone(),
+            stats: StatsV5::from(&s.stats),
+            bestiary: s
+                .bestiary
+                .iter()
+                .map(|(name, entry)| (name.clone(), BestiaryEntryV9::from(entry)))
+                .collect(),
         }
     }
 }
 
-impl From<ProgressV1> for Progress {
-    fn from(mut l: ProgressV1) -> Self {
+impl From<ProgressV9> for Progress {
+    fn from(mut l: ProgressV9) -> Self {
         Self {
             hp: l.hp,
             max_hp: l.max_hp,
@@ -567,13 +1087,105 @@ impl From<ProgressV1> for Progress {
             collected_chests: l.collected_chests,
             turned_levers: l.turned_levers,
             steps: l.steps,
-            earth_defeated: l.earth_defeated,
-            water_defeated: l.water_defeated,
-            fire_defeated: l.fire_defeated,
+            location: Location::from(l.location),
+            flags: l.flags.drain(..).collect(),
+            vars: l.vars,
+            stats: Stats::from(l.stats),
+            bestiary: l
+                .bestiary
+                .into_iter()
+                .map(|(name, entry)| (name, BestiaryEntry::from(entry)))
+                .collect(),
+        }
+    }
+}
+
+impl SaveSlotSummary {
+    pub fn load(slot: usize) -> Option<Self> {
+        if !save_data_exists(slot) {
+            return None;
+        }
+        let raw_summary = quad_storage::STORAGE
+            .lock()
+            .ok()?
+            .get(&slot_key(SAVE_SUMMARY_KEY, slot));
+        match raw_summary.and_then(|s| miniserde::json::from_str(&s).ok()) {
+            Some(summary) => Some(summary),
+            // Saves from before save slots existed have no summary, but could only be made in town.
+            None => Progress::load(slot).ok().map(|progress| Self {
+                level: progress.level,
+                location: String::from("Town"),
+                play_time: progress.stats.play_time,
+            }),
+        }
+    }
+}
+
+impl From<&Stats> for StatsV5 {
+    fn from(s: &Stats) -> Self {
+        Self {
+            play_time: s.play_time,
+            battles_fought: s.battles_fought,
+            battles_won: s.battles_won,
+            battles_fled: s.battles_fled,
+            battles_lost: s.battles_lost,
+            damage_dealt: s.damage_dealt,
+            damage_taken: s.damage_taken,
+            spells_cast: s.spells_cast,
+            items_used: s.items_used,
+            deaths: s.deaths,
         }
     }
 }
 
+impl From<StatsV5> for Stats {
+    fn from(l: StatsV5) -> Self {
+        Self {
+            play_time: l.play_time,
+            battles_fought: l.battles_fought,
+            battles_won: l.battles_won,
+            battles_fled: l.battles_fled,
+            battles_lost: l.battles_lost,
+            damage_dealt: l.damage_dealt,
+            damage_taken: l.damage_taken,
+            spells_cast: l.spells_cast,
+            items_used: l.items_used,
+            deaths: l.deaths,
+        }
+    }
+}
+
+pub fn any_save_data_exists() -> bool {
+    (0..NUM_SAVE_SLOTS).any(save_data_exists)
+}
+
+pub fn backup_exists(slot: usize) -> bool {
+    quad_storage::STORAGE.lock().is_ok_and(|s| {
+        (1..=NUM_SAVE_BACKUPS).any(|backup| s.get(&backup_key(SAVE_KEY, slot, backup)).is_some())
+    })
+}
+
+fn backup_key(base_key: &str, slot: usize, backup: usize) -> String {
+    assert!((1..=NUM_SAVE_BACKUPS).contains(&backup));
+    format!("{}_backup{backup}", slot_key(base_key, slot))
+}
+
+// 32-bit FNV-1a; this only needs to catch accidental damage, not tampering.
+fn checksum(data: &str) -> u32 {
+    data.bytes().fold(0x811c9dc5, |hash, b| {
+        (hash ^ u32::from(b)).wrapping_mul(0x01000193)
+    })
+}
+
+pub fn format_play_time(play_time: u32) -> String {
+    format!(
+        "{}:{:02}:{:02}",
+        play_time / 3600,
+        play_time / 60 % 60,
+        play_time % 60,
+    )
+}
+
 pub fn player_rank(level: i32) -> &'static str {
     match level {
         ..=7 => "Fighter",
@@ -584,8 +1196,18 @@ pub fn player_rank(level: i32) -> &'static str {
     }
 }
 
-pub fn save_data_exists() -> bool {
+pub fn save_data_exists(slot: usize) -> bool {
     quad_storage::STORAGE
         .lock()
-        .map_or(false, |s| s.get(SAVE_KEY).is_some())
+        .map_or(false, |s| s.get(&slot_key(SAVE_KEY, slot)).is_some())
+}
+
+pub fn slot_key(base_key: &str, slot: usize) -> String {
+    assert!(slot < NUM_SAVE_SLOTS);
+    // The first slot uses the bare key so that saves from before save slots still load.
+    if slot == 0 {
+        base_key.to_string()
+    } else {
+        format!("{base_key}{}", slot + 1)
+    }
 }
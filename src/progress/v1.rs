@@ -1,3 +1,5 @@
+use crate::storage::*;
+
 use miniserde::{Deserialize, Serialize};
 
 #[derive(Deserialize, Serialize)]
@@ -6,7 +8,7 @@ pub struct ArmorV1 {
     pub defense: i32,
 }
 
-#[derive(Clone, Copy, Deserialize, Serialize)]
+#[derive(Clone, Copy, Deserialize)]
 pub enum ItemV1 {
     Salve,
     XSalve,
@@ -14,7 +16,7 @@ pub enum ItemV1 {
     XTonic,
 }
 
-#[derive(Deserialize, Serialize)]
+#[derive(Deserialize)]
 pub struct ItemSlotV1 {
     pub item: ItemV1,
     pub amount: i32,
@@ -40,7 +42,7 @@ pub struct WeaponV1 {
     pub attack: i32,
 }
 
-#[derive(Deserialize, Serialize)]
+#[derive(Deserialize)]
 pub struct ProgressV1 {
     pub hp: i32,
     pub max_hp: i32,
@@ -64,20 +66,11 @@ pub struct ProgressV1 {
 }
 
 impl ProgressV1 {
-    pub fn from_str(data: &str, version: u32) -> Result<Self, &'static str> {
-        #[allow(clippy::comparison_chain)]
-        if version < 1 {
-            Err("invalid save version")
-        } else if version == 1 {
-            miniserde::json::from_str(data).map_err(|_| "failed to parse save data")
+    pub fn from_str(data: &str, version: u32) -> Result<Self, StorageError> {
+        if version == 1 {
+            from_json(data)
         } else {
-            Err("unknown save version")
+            Err(StorageError::UnknownVersion(version))
         }
     }
 }
-
-impl std::fmt::Display for ProgressV1 {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> Result<(), std::fmt::Error> {
-        f.write_str(&miniserde::json::to_string(self))
-    }
-}
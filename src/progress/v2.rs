@@ -0,0 +1,91 @@
+pub use super::v1::*;
+
+use crate::storage::*;
+
+use miniserde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Deserialize, Serialize)]
+pub enum DirectionV2 {
+    North,
+    East,
+    South,
+    West,
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct LocationV2 {
+    pub level_id: String,
+    pub x: i32,
+    pub y: i32,
+    pub dir: DirectionV2,
+}
+
+#[derive(Deserialize)]
+pub struct ProgressV2 {
+    pub hp: i32,
+    pub max_hp: i32,
+    pub mp: i32,
+    pub max_mp: i32,
+    pub attack: i32,
+    pub defense: i32,
+    pub level: i32,
+    pub exp: i32,
+    pub base_exp: i32,
+    pub weapon: Option<WeaponV1>,
+    pub armor: Option<ArmorV1>,
+    pub items: Vec<ItemSlotV1>,
+    pub magic: Vec<MagicSlotV1>,
+    pub collected_chests: Vec<String>,
+    pub turned_levers: Vec<String>,
+    pub steps: Vec<i32>,
+    pub earth_defeated: bool,
+    pub water_defeated: bool,
+    pub fire_defeated: bool,
+    pub location: LocationV2,
+}
+
+impl ProgressV2 {
+    pub fn from_str(data: &str, version: u32) -> Result<Self, StorageError> {
+        #[allow(clippy::comparison_chain)]
+        if version < 2 {
+            ProgressV1::from_str(data, version).map(Self::from)
+        } else if version == 2 {
+            from_json(data)
+        } else {
+            Err(StorageError::UnknownVersion(version))
+        }
+    }
+}
+
+impl From<ProgressV1> for ProgressV2 {
+    fn from(p: ProgressV1) -> Self {
+        Self {
+            hp: p.hp,
+            max_hp: p.max_hp,
+            mp: p.mp,
+            max_mp: p.max_mp,
+            attack: p.attack,
+            defense: p.defense,
+            level: p.level,
+            exp: p.exp,
+            base_exp: p.base_exp,
+            weapon: p.weapon,
+            armor: p.armor,
+            items: p.items,
+            magic: p.magic,
+            collected_chests: p.collected_chests,
+            turned_levers: p.turned_levers,
+            steps: p.steps,
+            earth_defeated: p.earth_defeated,
+            water_defeated: p.water_defeated,
+            fire_defeated: p.fire_defeated,
+            // V1 saves could only be made at the bed in town, so start the player beside it.
+            location: LocationV2 {
+                level_id: String::from("Start"),
+                x: 6,
+                y: 3,
+                dir: DirectionV2::South,
+            },
+        }
+    }
+}
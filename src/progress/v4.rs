@@ -0,0 +1,101 @@
+pub use super::v3::*;
+
+use crate::storage::*;
+
+use miniserde::Deserialize;
+use std::collections::BTreeMap;
+
+// V3 and earlier keyed chests and levers by level identifier, since each level had at most one
+// of each; these are the entity iids they referred to.
+const LEVEL_CHEST_IIDS: &[(&str, &str)] = &[
+    ("Level_2", "08be0f30-b0a0-11ee-880b-5b77cc5c1137"),
+    ("Earth_2", "1d124bc0-b0a0-11ee-90a3-9d449c086955"),
+    ("Earth_3", "8785cda0-b0a0-11ee-b7b5-4310f811c97f"),
+    ("Level_9", "7fff1070-b0a0-11ee-bbfd-2d8b9801b5f2"),
+    ("Water_2", "4254c4e0-b0a0-11ee-b3f6-6d508f4b22a4"),
+    ("Water_3", "4de71c40-b0a0-11ee-b3f6-371a348a799e"),
+    ("Level_17", "7fdc36c0-b0a0-11ee-ad39-a9f784ad2896"),
+    ("Fire_2", "d33171c0-b0a0-11ee-b5a1-a700c58a09f0"),
+    ("Fire_3", "d9b4c5b0-b0a0-11ee-b5a1-998184eaed4a"),
+];
+
+const LEVEL_LEVER_IIDS: &[(&str, &str)] = &[
+    ("Earth_2", "c517a660-b0a0-11ee-a94f-118d9ba43fb0"),
+    ("Earth_3", "857d5370-b0a0-11ee-b7b5-23baf6993590"),
+    ("Water_2", "3c75c150-b0a0-11ee-9125-395d9d53d2cd"),
+    ("Water_3", "cf173550-b0a0-11ee-afcc-dd22c009e286"),
+    ("Level_17", "8f031a60-b0a0-11ee-b8c8-43c704522d71"),
+    ("Fire_1", "b16b0e80-b0a0-11ee-bd83-156ce615cb34"),
+    ("Fire_2", "0f271b90-b0a0-11ee-ab6b-eb527238fb1d"),
+    ("Fire_3", "9c7e4e80-b0a0-11ee-84f7-83a00c8d4c1a"),
+];
+
+#[derive(Deserialize)]
+pub struct ProgressV4 {
+    pub hp: i32,
+    pub max_hp: i32,
+    pub mp: i32,
+    pub max_mp: i32,
+    pub attack: i32,
+    pub defense: i32,
+    pub level: i32,
+    pub exp: i32,
+    pub base_exp: i32,
+    pub weapon: Option<WeaponV1>,
+    pub armor: Option<ArmorV1>,
+    pub items: Vec<ItemSlotV1>,
+    pub magic: Vec<MagicSlotV1>,
+    pub collected_chests: Vec<String>,
+    pub turned_levers: Vec<String>,
+    pub steps: Vec<i32>,
+    pub location: LocationV2,
+    pub flags: Vec<String>,
+    pub vars: BTreeMap<String, i32>,
+}
+
+impl ProgressV4 {
+    pub fn from_str(data: &str, version: u32) -> Result<Self, StorageError> {
+        #[allow(clippy::comparison_chain)]
+        if version < 4 {
+            ProgressV3::from_str(data, version).map(Self::from)
+        } else if version == 4 {
+            from_json(data)
+        } else {
+            Err(StorageError::UnknownVersion(version))
+        }
+    }
+}
+
+impl From<ProgressV3> for ProgressV4 {
+    fn from(p: ProgressV3) -> Self {
+        fn level_ids_to_iids(level_ids: Vec<String>, table: &[(&str, &str)]) -> Vec<String> {
+            level_ids
+                .iter()
+                .filter_map(|level_id| table.iter().find(|(l, _)| l == level_id))
+                .map(|(_, iid)| String::from(*iid))
+                .collect()
+        }
+
+        Self {
+            hp: p.hp,
+            max_hp: p.max_hp,
+            mp: p.mp,
+            max_mp: p.max_mp,
+            attack: p.attack,
+            defense: p.defense,
+            level: p.level,
+            exp: p.exp,
+            base_exp: p.base_exp,
+            weapon: p.weapon,
+            armor: p.armor,
+            items: p.items,
+            magic: p.magic,
+            collected_chests: level_ids_to_iids(p.collected_chests, LEVEL_CHEST_IIDS),
+            turned_levers: level_ids_to_iids(p.turned_levers, LEVEL_LEVER_IIDS),
+            steps: p.steps,
+            location: p.location,
+            flags: p.flags,
+            vars: p.vars,
+        }
+    }
+}
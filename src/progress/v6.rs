@@ -0,0 +1,79 @@
+pub use super::v5::*;
+
+use crate::storage::*;
+
+use miniserde::Deserialize;
+use std::collections::BTreeMap;
+
+#[derive(Deserialize)]
+pub struct BestiaryEntryV6 {
+    pub seen: u32,
+    pub defeated: u32,
+    pub weakness_known: bool,
+}
+
+#[derive(Deserialize)]
+pub struct ProgressV6 {
+    pub hp: i32,
+    pub max_hp: i32,
+    pub mp: i32,
+    pub max_mp: i32,
+    pub attack: i32,
+    pub defense: i32,
+    pub level: i32,
+    pub exp: i32,
+    pub base_exp: i32,
+    pub weapon: Option<WeaponV1>,
+    pub armor: Option<ArmorV1>,
+    pub items: Vec<ItemSlotV1>,
+    pub magic: Vec<MagicSlotV1>,
+    pub collected_chests: Vec<String>,
+    pub turned_levers: Vec<String>,
+    pub steps: Vec<i32>,
+    pub location: LocationV2,
+    pub flags: Vec<String>,
+    pub vars: BTreeMap<String, i32>,
+    pub stats: StatsV5,
+    pub bestiary: BTreeMap<String, BestiaryEntryV6>,
+}
+
+impl ProgressV6 {
+    pub fn from_str(data: &str, version: u32) -> Result<Self, StorageError> {
+        #[allow(clippy::comparison_chain)]
+        if version < 6 {
+            ProgressV5::from_str(data, version).map(Self::from)
+        } else if version == 6 {
+            from_json(data)
+        } else {
+            Err(StorageError::UnknownVersion(version))
+        }
+    }
+}
+
+impl From<ProgressV5> for ProgressV6 {
+    fn from(p: ProgressV5) -> Self {
+        Self {
+            hp: p.hp,
+            max_hp: p.max_hp,
+            mp: p.mp,
+            max_mp: p.max_mp,
+            attack: p.attack,
+            defense: p.defense,
+            level: p.level,
+            exp: p.exp,
+            base_exp: p.base_exp,
+            weapon: p.weapon,
+            armor: p.armor,
+            items: p.items,
+            magic: p.magic,
+            collected_chests: p.collected_chests,
+            turned_levers: p.turned_levers,
+            steps: p.steps,
+            location: p.location,
+            flags: p.flags,
+            vars: p.vars,
+            stats: p.stats,
+            bestiary: BTreeMap::new(),
+        }
+    }
+}
@@ -0,0 +1,114 @@
+pub use super::v6::*;
+
+use crate::storage::*;
+
+use miniserde::Deserialize;
+use std::collections::BTreeMap;
+
+#[derive(Clone, Copy, Deserialize)]
+pub enum ItemV7 {
+    Salve,
+    XSalve,
+    Tonic,
+    XTonic,
+    Remedy,
+}
+
+#[derive(Deserialize)]
+pub struct ItemSlotV7 {
+    pub item: ItemV7,
+    pub amount: i32,
+}
+
+#[derive(Deserialize)]
+pub struct ProgressV7 {
+    pub hp: i32,
+    pub max_hp: i32,
+    pub mp: i32,
+    pub max_mp: i32,
+    pub attack: i32,
+    pub defense: i32,
+    pub level: i32,
+    pub exp: i32,
+    pub base_exp: i32,
+    pub weapon: Option<WeaponV1>,
+    pub armor: Option<ArmorV1>,
+    pub items: Vec<ItemSlotV7>,
+    pub magic: Vec<MagicSlotV1>,
+    pub collected_chests: Vec<String>,
+    pub turned_levers: Vec<String>,
+    pub steps: Vec<i32>,
+    pub location: LocationV2,
+    pub flags: Vec<String>,
+    pub vars: BTreeMap<String, i32>,
+    pub stats: StatsV5,
+    pub bestiary: BTreeMap<String, BestiaryEntryV6>,
+}
+
+impl ProgressV7 {
+    pub fn from_str(data: &str, version: u32) -> Result<Self, StorageError> {
+        #[allow(clippy::comparison_chain)]
+        if version < 7 {
+            ProgressV6::from_str(data, version).map(Self::from)
+        } else if version == 7 {
+            from_json(data)
+        } else {
+            Err(StorageError::UnknownVersion(version))
+        }
+    }
+}
+
+impl From<ItemV1> for ItemV7 {
+    fn from(l: ItemV1) -> Self {
+        match l {
+            ItemV1::Salve => Self::Salve,
+            ItemV1::XSalve => Self::XSalve,
+            ItemV1::Tonic => Self::Tonic,
+            ItemV1::XTonic => Self::XTonic,
+        }
+    }
+}
+
+impl From<ItemSlotV1> for ItemSlotV7 {
+    fn from(l: ItemSlotV1) -> Self {
+        Self {
+            item: l.item.into(),
+            amount: l.amount,
+        }
+    }
+}
+
+impl From<ProgressV6> for ProgressV7 {
+    fn from(p: ProgressV6) -> Self {
+        let mut items: Vec<ItemSlotV7> = p.items.into_iter().map(ItemSlotV7::from).collect();
+        // Remedies didn't exist before V7.
+        items.push(ItemSlotV7 {
+            item: ItemV7::Remedy,
+            amount: 0,
+        });
+
+        Self {
+            hp: p.hp,
+            max_hp: p.max_hp,
+            mp: p.mp,
+            max_mp: p.max_mp,
+            attack: p.attack,
+            defense: p.defense,
+            level: p.level,
+            exp: p.exp,
+            base_exp: p.base_exp,
+            weapon: p.weapon,
+            armor: p.armor,
+            items,
+            magic: p.magic,
+            collected_chests: p.collected_chests,
+            turned_levers: p.turned_levers,
+            steps: p.steps,
+            location: p.location,
+            flags: p.flags,
+            vars: p.vars,
+            stats: p.stats,
+            bestiary: p.bestiary,
+        }
+    }
+}
@@ -0,0 +1,116 @@
+pub use super::v7::*;
+
+use crate::storage::*;
+
+use miniserde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+#[derive(Clone, Copy, Deserialize, Serialize)]
+pub enum ItemV8 {
+    Salve,
+    XSalve,
+    Tonic,
+    XTonic,
+    Remedy,
+    Lens,
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct ItemSlotV8 {
+    pub item: ItemV8,
+    pub amount: i32,
+}
+
+#[derive(Deserialize)]
+pub struct ProgressV8 {
+    pub hp: i32,
+    pub max_hp: i32,
+    pub mp: i32,
+    pub max_mp: i32,
+    pub attack: i32,
+    pub defense: i32,
+    pub level: i32,
+    pub exp: i32,
+    pub base_exp: i32,
+    pub weapon: Option<WeaponV1>,
+    pub armor: Option<ArmorV1>,
+    pub items: Vec<ItemSlotV8>,
+    pub magic: Vec<MagicSlotV1>,
+    pub collected_chests: Vec<String>,
+    pub turned_levers: Vec<String>,
+    pub steps: Vec<i32>,
+    pub location: LocationV2,
+    pub flags: Vec<String>,
+    pub vars: BTreeMap<String, i32>,
+    pub stats: StatsV5,
+    pub bestiary: BTreeMap<String, BestiaryEntryV6>,
+}
+
+impl ProgressV8 {
+    pub fn from_str(data: &str, version: u32) -> Result<Self, StorageError> {
+        #[allow(clippy::comparison_chain)]
+        if version < 8 {
+            ProgressV7::from_str(data, version).map(Self::from)
+        } else if version == 8 {
+            from_json(data)
+        } else {
+            Err(StorageError::UnknownVersion(version))
+        }
+    }
+}
+
+impl From<ItemV7> for ItemV8 {
+    fn from(l: ItemV7) -> Self {
+        match l {
+            ItemV7::Salve => Self::Salve,
+            ItemV7::XSalve => Self::XSalve,
+            ItemV7::Tonic => Self::Tonic,
+            ItemV7::XTonic => Self::XTonic,
+            ItemV7::Remedy => Self::Remedy,
+        }
+    }
+}
+
+impl From<ItemSlotV7> for ItemSlotV8 {
+    fn from(l: ItemSlotV7) -> Self {
+        Self {
+            item: l.item.into(),
+            amount: l.amount,
+        }
+    }
+}
+
+impl From<ProgressV7> for ProgressV8 {
+    fn from(p: ProgressV7) -> Self {
+        let mut items: Vec<ItemSlotV8> = p.items.into_iter().map(ItemSlotV8::from).collect();
+        // Lenses didn't exist before V8.
+        items.push(ItemSlotV8 {
+            item: ItemV8::Lens,
+            amount: 0,
+        });
+
+        Self {
+            hp: p.hp,
+            max_hp: p.max_hp,
+            mp: p.mp,
+            max_mp: p.max_mp,
+            attack: p.attack,
+            defense: p.defense,
+            level: p.level,
+            exp: p.exp,
+            base_exp: p.base_exp,
+            weapon: p.weapon,
+            armor: p.armor,
+            items,
+            magic: p.magic,
+            collected_chests: p.collected_chests,
+            turned_levers: p.turned_levers,
+            steps: p.steps,
+            location: p.location,
+            flags: p.flags,
+            vars: p.vars,
+            stats: p.stats,
+            bestiary: p.bestiary,
+        }
+    }
+}
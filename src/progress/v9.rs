@@ -0,0 +1,104 @@
+pub use super::v8::*;
+
+use crate::storage::*;
+
+use miniserde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+#[derive(Deserialize, Serialize)]
+pub struct BestiaryEntryV9 {
+    pub seen: u32,
+    pub defeated: u32,
+    pub known_affinities: Vec<MagicV1>,
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct ProgressV9 {
+    pub hp: i32,
+    pub max_hp: i32,
+    pub mp: i32,
+    pub max_mp: i32,
+    pub attack: i32,
+    pub defense: i32,
+    pub level: i32,
+    pub exp: i32,
+    pub base_exp: i32,
+    pub weapon: Option<WeaponV1>,
+    pub armor: Option<ArmorV1>,
+    pub items: Vec<ItemSlotV8>,
+    pub magic: Vec<MagicSlotV1>,
+    pub collected_chests: Vec<String>,
+    pub turned_levers: Vec<String>,
+    pub steps: Vec<i32>,
+    pub location: LocationV2,
+    pub flags: Vec<String>,
+    pub vars: BTreeMap<String, i32>,
+    pub stats: StatsV5,
+    pub bestiary: BTreeMap<String, BestiaryEntryV9>,
+}
+
+impl ProgressV9 {
+    pub fn from_str(data: &str, version: u32) -> Result<Self, StorageError> {
+        #[allow(clippy::comparison_chain)]
+        if version < 9 {
+            ProgressV8::from_str(data, version).map(Self::from)
+        } else if version == 9 {
+            from_json(data)
+        } else {
+            Err(StorageError::UnknownVersion(version))
+        }
+    }
+}
+
+impl From<BestiaryEntryV6> for BestiaryEntryV9 {
+    fn from(l: BestiaryEntryV6) -> Self {
+        Self {
+            seen: l.seen,
+            defeated: l.defeated,
+            // The old flag stood for every element at once.
+            known_affinities: if l.weakness_known {
+                vec![MagicV1::EarthEdge, MagicV1::WaterEdge, MagicV1::FireEdge]
+            } else {
+                Vec::new()
+            },
+        }
+    }
+}
+
+impl From<ProgressV8> for ProgressV9 {
+    fn from(p: ProgressV8) -> Self {
+        Self {
+            hp: p.hp,
+            max_hp: p.max_hp,
+            mp: p.mp,
+            max_mp: p.max_mp,
+            attack: p.attack,
+            defense: p.defense,
+            level: p.level,
+            exp: p.exp,
+            base_exp: p.base_exp,
+            weapon: p.weapon,
+            armor: p.armor,
+            items: p.items,
+            magic: p.magic,
+            collected_chests: p.collected_chests,
+            turned_levers: p.turned_levers,
+            steps: p.steps,
+            location: p.location,
+            flags: p.flags,
+            vars: p.vars,
+            stats: p.stats,
+            bestiary: p
+                .bestiary
+                .into_iter()
+                .map(|(name, entry)| (name, BestiaryEntryV9::from(entry)))
+                .collect(),
+        }
+    }
+}
+
+impl std::fmt::Display for ProgressV9 {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> Result<(), std::fmt::Error> {
+        f.write_str(&miniserde::json::to_string(self))
+    }
+}
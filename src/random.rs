@@ -18,6 +18,11 @@ impl Rng {
         }
     }
 
+    // Picks up exactly where an earlier `state` left off.
+    pub fn from_state(state: [u32; 4]) -> Self {
+        Self { state }
+    }
+
     pub fn random(&mut self, s: u32) -> u32 {
         // Use Daniel Lemire's algorithm to get a random number from zero to s.
         let mut x = self.xoshiro128pp_next();
@@ -37,6 +42,10 @@ impl Rng {
         (m >> 32) as u32
     }
 
+    pub fn state(&self) -> [u32; 4] {
+        self.state
+    }
+
     // xoshiro128++ adapted from https://prng.di.unimi.it/xoshiro128plusplus.c
     fn xoshiro128pp_next(&mut self) -> u32 {
         let result = self.state[0]
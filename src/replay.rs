@@ -0,0 +1,226 @@
+// Battles are recorded so that they can be played again exactly as they happened, e.g. to
+// reproduce a bug report.
+//
+// A battle only depends on its starting state and the keys pressed on each frame, so that's all
+// that's recorded, along with a snapshot of HP at the start of each turn and how the battle ended.
+// Replaying presses the recorded keys on the same frames, and compares the snapshots and ending
+// as they come up to find where the replay diverged, if anywhere.
+
+use crate::async_utils::*;
+use crate::enemy::*;
+use crate::input::*;
+use crate::progress::*;
+use crate::random::*;
+use crate::saved_options::*;
+use crate::storage::*;
+
+use miniserde::{Deserialize, Serialize};
+use std::future::Future;
+
+// The most recent battle is recorded here, in the working directory.
+pub const REPLAY_PATH: &str = "battle.replay";
+
+#[derive(Deserialize, Serialize)]
+pub struct BattleReplay {
+    pub rng_state: Vec<u32>,
+    // Progress as save data.
+    pub progress: String,
+    pub enemies: Vec<String>,
+    pub boss_fight: bool,
+    // Text speed and auto-advance change how many frames each message takes.
+    pub battle_speed: BattleSpeed,
+    // Only frames with keys pressed are listed.
+    pub inputs: Vec<ReplayInput>,
+    pub turns: Vec<ReplayTurn>,
+    pub frames: u32,
+    pub outcome: String,
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct ReplayInput {
+    pub frame: u32,
+    // One bit per `GameKey`.
+    pub keys: u8,
+}
+
+#[derive(Deserialize, PartialEq, Serialize)]
+pub struct ReplayTurn {
+    pub frame: u32,
+    pub hp: i32,
+    pub mp: i32,
+    pub enemy_hp: Vec<i32>,
+}
+
+pub struct BattleRecorder {
+    recording: BattleReplay,
+    // The replay being played back, if any.
+    expected: Option<BattleReplay>,
+    frame: u32,
+    divergence: Option<String>,
+}
+
+impl BattleReplay {
+    pub fn load() -> Result<Self, String> {
+        let data = std::fs::read_to_string(REPLAY_PATH).map_err(|e| e.to_string())?;
+        let replay: Self = from_json(&data).map_err(|e| e.to_string())?;
+        if replay.rng_state.len() != 4 {
+            return Err(String::from("rng_state must have 4 values"));
+        }
+        Ok(replay)
+    }
+
+    pub fn rng(&self) -> Rng {
+        Rng::from_state(self.rng_state[..].try_into().expect("4 rng_state values"))
+    }
+}
+
+impl BattleRecorder {
+    pub fn new(
+        rng: &Rng,
+        progress: &Progress,
+        enemies: &[Enemy],
+        boss_fight: bool,
+        battle_speed: BattleSpeed,
+    ) -> Self {
+        Self {
+            recording: BattleReplay {
+                rng_state: rng.state().to_vec(),
+                progress: progress.to_save_data(),
+                enemies: enemies.iter().map(|e| e.name.clone()).collect(),
+                boss_fight,
+                battle_speed,
+                inputs: Vec::new(),
+                turns: Vec::new(),
+                frames: 0,
+                outcome: String::new(),
+            },
+            expected: None,
+            frame: 0,
+            divergence: None,
+        }
+    }
+
+    pub fn replaying(replay: BattleReplay) -> Self {
+        Self {
+            recording: BattleReplay {
+                rng_state: replay.rng_state.clone(),
+                progress: replay.progress.clone(),
+                enemies: replay.enemies.clone(),
+                boss_fight: replay.boss_fight,
+                battle_speed: replay.battle_speed,
+                inputs: Vec::new(),
+                turns: Vec::new(),
+                frames: 0,
+                outcome: String::new(),
+            },
+            expected: Some(replay),
+            frame: 0,
+            divergence: None,
+        }
+    }
+
+    // Only the first divergence is kept, since everything after it is bound to differ too.
+    fn diverge(&mut self, place: String, details: String) {
+        if self.divergence.is_none() {
+            eprintln!("replay diverged {place}: {details}");
+            self.divergence = Some(place);
+        }
+    }
+
+    // Where the replay first went differently from the recording, if it did, e.g. "on turn 3".
+    pub fn divergence(&self) -> Option<&str> {
+        self.divergence.as_deref()
+    }
+
+    pub fn finish(&mut self, outcome: &str) {
+        self.recording.frames = self.frame;
+        self.recording.outcome = String::from(outcome);
+
+        let Some(expected) = &self.expected else {
+            return;
+        };
+        if expected.outcome != outcome || expected.frames != self.frame {
+            let details = format!(
+                "ended in {} on frame {}, but the recording ended in {} on frame {}",
+                outcome, self.frame, expected.outcome, expected.frames,
+            );
+            self.diverge(String::from("at the end"), details);
+        }
+    }
+
+    // Presses the recorded keys for this frame instead of the real ones when replaying, or
+    // records the real ones otherwise.
+    fn next_frame(&mut self, input: &mut Input) {
+        if let Some(expected) = &self.expected {
+            let keys = expected
+                .inputs
+                .iter()
+                .find(|i| i.frame == self.frame)
+                .map_or(0, |i| i.keys);
+            input.set_pressed_bits(keys);
+        }
+        let keys = input.pressed_bits();
+        if keys != 0 {
+            self.recording.inputs.push(ReplayInput {
+                frame: self.frame,
+                keys,
+            });
+        }
+        self.frame += 1;
+    }
+
+    pub fn record_turn(&mut self, hp: i32, mp: i32, enemy_hp: Vec<i32>) {
+        let turn = ReplayTurn {
+            frame: self.frame,
+            hp,
+            mp,
+            enemy_hp,
+        };
+        let n = self.recording.turns.len();
+        if let Some(expected) = &self.expected {
+            match expected.turns.get(n) {
+                Some(expected_turn) if *expected_turn == turn => {}
+                Some(expected_turn) => {
+                    let details = format!(
+                        "started on frame {} with HP {}, MP {} and enemy HP {:?}, \
+                         but was recorded on frame {} with HP {}, MP {} and enemy HP {:?}",
+                        turn.frame,
+                        turn.hp,
+                        turn.mp,
+                        turn.enemy_hp,
+                        expected_turn.frame,
+                        expected_turn.hp,
+                        expected_turn.mp,
+                        expected_turn.enemy_hp,
+                    );
+                    self.diverge(format!("on turn {}", n + 1), details);
+                }
+                None => self.diverge(
+                    format!("on turn {}", n + 1),
+                    String::from("the recording ended before this turn"),
+                ),
+            }
+        }
+        self.recording.turns.push(turn);
+    }
+
+    pub fn save(&self) -> Result<(), String> {
+        std::fs::write(REPLAY_PATH, miniserde::json::to_string(&self.recording))
+            .map_err(|e| e.to_string())
+    }
+}
+
+// Polls a battle once per frame like usual, but records the keys pressed on every frame along the
+// way, or presses the recorded ones when replaying.
+pub async fn record_battle<F: Future>(
+    mut recorder: SharedMut<BattleRecorder>,
+    mut input: SharedMut<Input>,
+    battle: F,
+) -> F::Output {
+    let mut battle = std::pin::pin!(battle);
+    std::future::poll_fn(|cx| {
+        recorder.next_frame(&mut input);
+        battle.as_mut().poll(cx)
+    })
+    .await
+}
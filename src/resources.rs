@@ -1,4 +1,5 @@
 use crate::aseprite;
+use crate::enemy::*;
 use crate::levels::*;
 use crate::shaders::*;
 
@@ -47,6 +48,19 @@ const SPRITE_SHEETS_BY_PATH: &[(&str, &str)] = &[
     ("scorpion.png", include_str!("../assets/scorpion.json")),
     ("serpent.png", include_str!("../assets/serpent.json")),
     ("shambler.png", include_str!("../assets/shambler.json")),
+    (
+        "spell-earth-edge.png",
+        include_str!("../assets/spell-earth-edge.json"),
+    ),
+    (
+        "spell-fire-edge.png",
+        include_str!("../assets/spell-fire-edge.json"),
+    ),
+    ("spell-heal.png", include_str!("../assets/spell-heal.json")),
+    (
+        "spell-water-edge.png",
+        include_str!("../assets/spell-water-edge.json"),
+    ),
     ("title.png", include_str!("../assets/title.json")),
     ("troll.png", include_str!("../assets/troll.json")),
     ("turtle.png", include_str!("../assets/turtle.json")),
@@ -62,6 +76,34 @@ const SPRITE_SHEETS_BY_PATH: &[(&str, &str)] = &[
 
 const TEXTURES_BY_PATH: &[(&str, &[u8])] = &[
     ("air.png", include_bytes!("../assets/air.png")),
+    (
+        "backdrop-earth-lair.png",
+        include_bytes!("../assets/backdrop-earth-lair.png"),
+    ),
+    (
+        "backdrop-fire-lair.png",
+        include_bytes!("../assets/backdrop-fire-lair.png"),
+    ),
+    (
+        "backdrop-flooded.png",
+        include_bytes!("../assets/backdrop-flooded.png"),
+    ),
+    (
+        "backdrop-grassland.png",
+        include_bytes!("../assets/backdrop-grassland.png"),
+    ),
+    (
+        "backdrop-lava.png",
+        include_bytes!("../assets/backdrop-lava.png"),
+    ),
+    (
+        "backdrop-stone.png",
+        include_bytes!("../assets/backdrop-stone.png"),
+    ),
+    (
+        "backdrop-water-lair.png",
+        include_bytes!("../assets/backdrop-water-lair.png"),
+    ),
     ("base.png", include_bytes!("../assets/base.png")),
     ("basilisk.png", include_bytes!("../assets/basilisk.png")),
     ("bat.png", include_bytes!("../assets/bat.png")),
@@ -100,6 +142,19 @@ const TEXTURES_BY_PATH: &[(&str, &[u8])] = &[
     ("scorpion.png", include_bytes!("../assets/scorpion.png")),
     ("serpent.png", include_bytes!("../assets/serpent.png")),
     ("shambler.png", include_bytes!("../assets/shambler.png")),
+    (
+        "spell-earth-edge.png",
+        include_bytes!("../assets/spell-earth-edge.png"),
+    ),
+    (
+        "spell-fire-edge.png",
+        include_bytes!("../assets/spell-fire-edge.png"),
+    ),
+    ("spell-heal.png", include_bytes!("../assets/spell-heal.png")),
+    (
+        "spell-water-edge.png",
+        include_bytes!("../assets/spell-water-edge.png"),
+    ),
     ("title.png", include_bytes!("../assets/title.png")),
     ("troll.png", include_bytes!("../assets/troll.png")),
     ("turtle.png", include_bytes!("../assets/turtle.png")),
@@ -119,6 +174,7 @@ pub struct Resources {
     pub layer_pipeline: Pipeline,
     pub quad_pipeline: Pipeline,
     pub levels: LevelSet,
+    pub enemies: EnemySet,
     pub font: Texture,
     pub window_textures: WindowTextures,
     pub textures_by_path: HashMap<&'static str, Texture>,
@@ -164,12 +220,25 @@ impl Resources {
             })
             .collect::<HashMap<_, _>>();
 
+        let enemies = EnemySet::new();
+        for enemy in enemies.all() {
+            let Some(sheet) = sprite_sheets_by_path.get(enemy.sprite_path.as_str()) else {
+                panic!("{}: unknown sprite_path: {}", enemy.name, enemy.sprite_path);
+            };
+            for animation in enemy.phases.iter().filter_map(|p| p.animation.as_ref()) {
+                if !sheet.meta.frame_tags.iter().any(|t| &t.name == animation) {
+                    panic!("{}: unknown phase animation: {animation}", enemy.name);
+                }
+            }
+        }
+
         Self {
             quad_vbuf,
             quad_ibuf,
             layer_pipeline,
             quad_pipeline,
             levels: LevelSet::new(),
+            enemies,
             font,
             window_textures,
             textures_by_path,
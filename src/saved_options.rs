@@ -1,4 +1,5 @@
 use crate::audio::*;
+use crate::storage::*;
 
 use miniserde::{Deserialize, Serialize};
 
@@ -6,8 +7,26 @@ use miniserde::{Deserialize, Serialize};
 pub struct SavedOptions {
     pub music_volume: u8,
     pub sound_volume: u8,
+    // Missing from options saved before battle speed could be changed.
+    pub battle_speed: Option<BattleSpeed>,
 }
 
+#[derive(Clone, Copy, Deserialize, PartialEq, Serialize)]
+pub struct BattleSpeed {
+    pub text_speed: TextSpeed,
+    // Seconds to wait before moving on from a battle message by itself, or 0 to wait for Confirm.
+    pub auto_advance: u8,
+}
+
+#[derive(Clone, Copy, Deserialize, PartialEq, Serialize)]
+pub enum TextSpeed {
+    Normal,
+    Fast,
+    Instant,
+}
+
+pub const MAX_AUTO_ADVANCE: u8 = 3;
+
 const OPTIONS_KEY: &str = "options";
 
 impl SavedOptions {
@@ -15,24 +34,83 @@ impl SavedOptions {
         Self {
             music_volume: MAX_MUSIC_VOLUME,
             sound_volume: MAX_SOUND_VOLUME,
+            battle_speed: Some(BattleSpeed::new()),
         }
     }
 
-    pub fn load() -> Result<Self, &'static str> {
+    pub fn load() -> Result<Self, StorageError> {
         let raw_data = quad_storage::STORAGE
             .lock()
-            .map_err(|_| "storage error")?
+            .map_err(|_| StorageError::Unavailable)?
             .get(OPTIONS_KEY)
-            .ok_or("no option file found")?;
-        miniserde::json::from_str(&raw_data).map_err(|_| "failed to parse options data")
+            .ok_or(StorageError::Missing)?;
+        let opts: Self = from_json(&raw_data)?;
+        if opts.music_volume > MAX_MUSIC_VOLUME {
+            return Err(StorageError::InvalidValue(String::from("music_volume")));
+        }
+        if opts.sound_volume > MAX_SOUND_VOLUME {
+            return Err(StorageError::InvalidValue(String::from("sound_volume")));
+        }
+        if opts
+            .battle_speed
+            .is_some_and(|b| b.auto_advance > MAX_AUTO_ADVANCE)
+        {
+            return Err(StorageError::InvalidValue(String::from("auto_advance")));
+        }
+        Ok(opts)
     }
 
-    pub fn save(&self) -> Result<(), &'static str> {
+    pub fn save(&self) -> Result<(), StorageError> {
         let raw_data = miniserde::json::to_string(self);
         quad_storage::STORAGE
             .lock()
-            .map_err(|_| "storage error")?
+            .map_err(|_| StorageError::Unavailable)?
             .set(OPTIONS_KEY, &raw_data);
         Ok(())
     }
 }
+
+impl BattleSpeed {
+    pub fn new() -> Self {
+        Self {
+            text_speed: TextSpeed::Normal,
+            auto_advance: 0,
+        }
+    }
+
+    pub fn auto_advance_frames(&self) -> Option<i32> {
+        (self.auto_advance > 0).then_some(self.auto_advance as i32 * 60)
+    }
+}
+
+impl TextSpeed {
+    pub fn chars_per_frame(&self) -> i32 {
+        match self {
+            Self::Normal => 1,
+            Self::Fast => 3,
+            Self::Instant => i32::MAX,
+        }
+    }
+
+    pub fn faster(&self) -> Self {
+        match self {
+            Self::Normal => Self::Fast,
+            Self::Fast | Self::Instant => Self::Instant,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Normal => "Normal",
+            Self::Fast => "Fast",
+            Self::Instant => "Instant",
+        }
+    }
+
+    pub fn slower(&self) -> Self {
+        match self {
+            Self::Normal | Self::Fast => Self::Normal,
+            Self::Instant => Self::Fast,
+        }
+    }
+}
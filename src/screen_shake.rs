@@ -0,0 +1,36 @@
+use crate::async_utils::wait_once;
+
+// How far the whole screen is moved in pixels, applied when drawing the offscreen image.
+pub struct ScreenShake([i32; 2]);
+
+// Directions to move the screen in as it shakes, two frames each.
+const SHAKE_PATTERN: [[i32; 2]; 4] = [[1, 0], [-1, 1], [1, -1], [-1, 0]];
+
+impl ScreenShake {
+    pub fn new() -> Self {
+        Self([0, 0])
+    }
+
+    // Shakes the screen back and forth, settling down to nothing over the given frames.
+    pub async fn shake(&mut self, frames: u16, strength: i32) {
+        for frame in 0..frames {
+            let amount = strength * (frames - frame) as i32 / frames as i32;
+            let [x, y] = SHAKE_PATTERN[frame as usize / 2 % SHAKE_PATTERN.len()];
+            self.0 = [x * amount, y * amount / 2];
+            wait_once().await;
+        }
+        self.0 = [0, 0];
+    }
+}
+
+impl Default for ScreenShake {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl From<&ScreenShake> for [i32; 2] {
+    fn from(screen_shake: &ScreenShake) -> Self {
+        screen_shake.0
+    }
+}
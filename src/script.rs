@@ -1,10 +1,14 @@
 use crate::actor::*;
+use crate::async_utils::SharedMut;
 use crate::audio::*;
 use crate::contexts::*;
+use crate::direction::*;
 use crate::enemy::*;
 use crate::levels::TILE_SIZE;
 use crate::modes::*;
 use crate::progress::*;
+use crate::replay::*;
+use crate::storage::*;
 use crate::wait_once;
 
 use std::collections::HashSet;
@@ -46,32 +50,39 @@ static LEVEL_SCRIPTS: &[LevelScripts] = &[
                     }
 
                     sctx.push_yes_no_prompt_mode("Save your progress?", "Save", "Don't save", true);
-                    let mut do_save = matches!(
+                    let do_save = matches!(
                         sctx.update_yes_no_prompt_mode().await,
                         YesNoPromptEvent::Yes
                     );
                     sctx.pop_mode();
 
-                    if do_save && sctx.confirm_save_overwrite {
-                        sctx.push_yes_no_prompt_mode(
-                            "Save data exists; overwrite it?",
-                            "Overwrite",
-                            "Cancel",
-                            false,
-                        );
-                        do_save = matches!(
-                            sctx.update_yes_no_prompt_mode().await,
-                            YesNoPromptEvent::Yes
-                        );
+                    let mut save_slot = None;
+                    if do_save {
+                        sctx.push_slot_picker_mode("Save to which slot?", true);
+                        if let SlotPickerEvent::Slot(slot) = sctx.update_slot_picker_mode().await {
+                            save_slot = Some(slot);
+                        }
                         sctx.pop_mode();
                     }
 
-                    if do_save {
-                        match sctx.progress.save() {
-                            Ok(()) => {
-                                sctx.push_text_box_mode("Progress has been saved.");
-                                sctx.confirm_save_overwrite = false;
+                    if let Some(slot) = save_slot {
+                        if sctx.confirm_save_overwrite[slot] {
+                            sctx.push_yes_no_prompt_mode(
+                                "Save data exists; overwrite it?",
+                                "Overwrite",
+                                "Cancel",
+                                false,
+                            );
+                            if let YesNoPromptEvent::No = sctx.update_yes_no_prompt_mode().await {
+                                save_slot = None;
                             }
+                            sctx.pop_mode();
+                        }
+                    }
+
+                    if let Some(slot) = save_slot {
+                        match sctx.save_progress(slot) {
+                            Ok(()) => sctx.push_text_box_mode("Progress has been saved."),
                             Err(e) => sctx.push_text_box_mode(&format!("Save error:\n{e}")),
                         }
                         let TextBoxEvent::Done = sctx.update_text_box_mode().await;
@@ -81,9 +92,9 @@ static LEVEL_SCRIPTS: &[LevelScripts] = &[
             }),
             (ActorType::Ducille, |sctx| {
                 Box::pin(async {
-                    let (salves, xsalves, tonics) = if !sctx.progress.earth_defeated {
+                    let (salves, xsalves, tonics) = if !sctx.progress.flag(EARTH_DEFEATED) {
                         (1, 0, 1)
-                    } else if !sctx.progress.water_defeated {
+                    } else if !sctx.progress.flag(WATER_DEFEATED) {
                         (2, 0, 2)
                     } else {
                         (1, 1, 2)
@@ -131,7 +142,7 @@ static LEVEL_SCRIPTS: &[LevelScripts] = &[
                         sctx.pop_mode();
                     }
 
-                    if !sctx.progress.earth_defeated {
+                    if !sctx.progress.flag(EARTH_DEFEATED) {
                         sctx.push_text_box_mode(
                             "Ducille:\n\
                              You can rest in your bed\n\
@@ -139,7 +150,7 @@ static LEVEL_SCRIPTS: &[LevelScripts] = &[
                         );
                         let TextBoxEvent::Done = sctx.update_text_box_mode().await;
                         sctx.pop_mode();
-                    } else if !sctx.progress.water_defeated {
+                    } else if !sctx.progress.flag(WATER_DEFEATED) {
                         sctx.push_text_box_mode(
                             "Ducille:\n\
                              If you fall in battle,\n\
@@ -147,7 +158,7 @@ static LEVEL_SCRIPTS: &[LevelScripts] = &[
                         );
                         let TextBoxEvent::Done = sctx.update_text_box_mode().await;
                         sctx.pop_mode();
-                    } else if !sctx.progress.fire_defeated {
+                    } else if !sctx.progress.flag(FIRE_DEFEATED) {
                         sctx.push_text_box_mode(
                             "Ducille:\n\
                              The spirits were possessed, you say?\n\
@@ -187,7 +198,7 @@ static LEVEL_SCRIPTS: &[LevelScripts] = &[
                         sctx.pop_mode();
                     }
 
-                    if !sctx.progress.earth_defeated {
+                    if !sctx.progress.flag(EARTH_DEFEATED) {
                         sctx.push_text_box_mode(
                             "Jace:\n\
                              The spirits reside in three castles.\n\
@@ -195,7 +206,7 @@ static LEVEL_SCRIPTS: &[LevelScripts] = &[
                         );
                         let TextBoxEvent::Done = sctx.update_text_box_mode().await;
                         sctx.pop_mode();
-                    } else if !sctx.progress.water_defeated {
+                    } else if !sctx.progress.flag(WATER_DEFEATED) {
                         sctx.push_text_box_mode(
                             "Jace:\n\
                              Head to the Water Castle, across\n\
@@ -203,7 +214,7 @@ static LEVEL_SCRIPTS: &[LevelScripts] = &[
                         );
                         let TextBoxEvent::Done = sctx.update_text_box_mode().await;
                         sctx.pop_mode();
-                    } else if !sctx.progress.fire_defeated {
+                    } else if !sctx.progress.flag(FIRE_DEFEATED) {
                         sctx.push_text_box_mode(
                             "Jace:\n\
                              Across the chasms and cliffs\n\
@@ -216,7 +227,7 @@ static LEVEL_SCRIPTS: &[LevelScripts] = &[
             }),
             (ActorType::Julis, |sctx| {
                 Box::pin(async {
-                    if !sctx.progress.earth_defeated {
+                    if !sctx.progress.flag(EARTH_DEFEATED) {
                         sctx.push_text_box_mode(
                             "Julis:\n\
                              Press Left Ctrl to view your status,\n\
@@ -232,7 +243,7 @@ static LEVEL_SCRIPTS: &[LevelScripts] = &[
                         );
                         let TextBoxEvent::Done = sctx.update_text_box_mode().await;
                         sctx.pop_mode();
-                    } else if !sctx.progress.water_defeated {
+                    } else if !sctx.progress.flag(WATER_DEFEATED) {
                         sctx.push_text_box_mode(
                             "Julis:\n\
                              Ducille tends to the apocathery;\n\
@@ -240,7 +251,7 @@ static LEVEL_SCRIPTS: &[LevelScripts] = &[
                         );
                         let TextBoxEvent::Done = sctx.update_text_box_mode().await;
                         sctx.pop_mode();
-                    } else if !sctx.progress.fire_defeated {
+                    } else if !sctx.progress.flag(FIRE_DEFEATED) {
                         sctx.push_text_box_mode(
                             "Julis:\n\
                              We have records of vampires\n\
@@ -283,7 +294,7 @@ static LEVEL_SCRIPTS: &[LevelScripts] = &[
                         sctx.pop_mode();
                     }
 
-                    if !sctx.progress.earth_defeated {
+                    if !sctx.progress.flag(EARTH_DEFEATED) {
                         sctx.push_text_box_mode(
                             "Matero:\n\
                              If you use magic on a foe, you can\n\
@@ -291,7 +302,7 @@ static LEVEL_SCRIPTS: &[LevelScripts] = &[
                         );
                         let TextBoxEvent::Done = sctx.update_text_box_mode().await;
                         sctx.pop_mode();
-                    } else if !sctx.progress.water_defeated {
+                    } else if !sctx.progress.flag(WATER_DEFEATED) {
                         sctx.push_text_box_mode(
                             "Matero:\n\
                              Rumor has it that each castle has\n\
@@ -299,7 +310,7 @@ static LEVEL_SCRIPTS: &[LevelScripts] = &[
                         );
                         let TextBoxEvent::Done = sctx.update_text_box_mode().await;
                         sctx.pop_mode();
-                    } else if !sctx.progress.fire_defeated {
+                    } else if !sctx.progress.flag(FIRE_DEFEATED) {
                         sctx.push_text_box_mode(
                             "Matero:\n\
                              If spikes block your path, you can\n\
@@ -316,7 +327,7 @@ static LEVEL_SCRIPTS: &[LevelScripts] = &[
         level_name: "Level_10",
         on_enter: Some(|sctx| {
             Box::pin(async {
-                if !sctx.progress.earth_defeated {
+                if !sctx.progress.flag(EARTH_DEFEATED) {
                     sctx.place_gates(9, 10);
                 }
             })
@@ -327,7 +338,7 @@ static LEVEL_SCRIPTS: &[LevelScripts] = &[
         level_name: "Earth_4",
         on_enter: Some(|sctx| {
             Box::pin(async {
-                if sctx.progress.earth_defeated {
+                if sctx.progress.flag(EARTH_DEFEATED) {
                     let earth = sctx
                         .actors
                         .iter()
@@ -345,7 +356,8 @@ static LEVEL_SCRIPTS: &[LevelScripts] = &[
                 let TextBoxEvent::Done = sctx.update_text_box_mode().await;
                 sctx.pop_mode();
 
-                sctx.push_battle_mode(Enemy::earth_spirit(), true);
+                let enemy = sctx.res.enemies.by_name("Earth");
+                sctx.push_battle_mode(vec![enemy], true);
 
                 let earth = sctx
                     .actors
@@ -365,7 +377,7 @@ static LEVEL_SCRIPTS: &[LevelScripts] = &[
                 sctx.pop_mode();
 
                 sctx.actors.remove(earth);
-                sctx.progress.earth_defeated = true;
+                sctx.progress.set_flag(EARTH_DEFEATED, true);
             })
         })],
     },
@@ -373,7 +385,7 @@ static LEVEL_SCRIPTS: &[LevelScripts] = &[
         level_name: "Level_17",
         on_enter: Some(|sctx| {
             Box::pin(async {
-                if !sctx.progress.water_defeated {
+                if !sctx.progress.flag(WATER_DEFEATED) {
                     sctx.place_gates(29, 12);
                 }
             })
@@ -384,7 +396,7 @@ static LEVEL_SCRIPTS: &[LevelScripts] = &[
         level_name: "Water_4",
         on_enter: Some(|sctx| {
             Box::pin(async {
-                if sctx.progress.water_defeated {
+                if sctx.progress.flag(WATER_DEFEATED) {
                     let water = sctx
                         .actors
                         .iter()
@@ -400,7 +412,8 @@ static LEVEL_SCRIPTS: &[LevelScripts] = &[
                 let TextBoxEvent::Done = sctx.update_text_box_mode().await;
                 sctx.pop_mode();
 
-                sctx.push_battle_mode(Enemy::water_spirit(), true);
+                let enemy = sctx.res.enemies.by_name("Water");
+                sctx.push_battle_mode(vec![enemy], true);
 
                 let water = sctx
                     .actors
@@ -420,7 +433,7 @@ static LEVEL_SCRIPTS: &[LevelScripts] = &[
                 sctx.pop_mode();
 
                 sctx.actors.remove(water);
-                sctx.progress.water_defeated = true;
+                sctx.progress.set_flag(WATER_DEFEATED, true);
             })
         })],
     },
@@ -428,7 +441,7 @@ static LEVEL_SCRIPTS: &[LevelScripts] = &[
         level_name: "Fire_4",
         on_enter: Some(|sctx| {
             Box::pin(async {
-                if sctx.progress.fire_defeated {
+                if sctx.progress.flag(FIRE_DEFEATED) {
                     let fire = sctx
                         .actors
                         .iter()
@@ -444,7 +457,8 @@ static LEVEL_SCRIPTS: &[LevelScripts] = &[
                 let TextBoxEvent::Done = sctx.update_text_box_mode().await;
                 sctx.pop_mode();
 
-                sctx.push_battle_mode(Enemy::fire_spirit(), true);
+                let enemy = sctx.res.enemies.by_name("Fire");
+                sctx.push_battle_mode(vec![enemy], true);
 
                 let fire = sctx
                     .actors
@@ -464,7 +478,7 @@ static LEVEL_SCRIPTS: &[LevelScripts] = &[
                 sctx.pop_mode();
 
                 sctx.actors.remove(fire);
-                sctx.progress.fire_defeated = true;
+                sctx.progress.set_flag(FIRE_DEFEATED, true);
             })
         })],
     },
@@ -480,13 +494,15 @@ pub async fn script_main(mut sctx: ScriptContext) {
         match sctx.update_title_mode().await {
             TitleEvent::NewGame(false) => {
                 // Start a new game, but skip the intro.
-                sctx.confirm_save_overwrite = save_data_exists();
+                sctx.confirm_save_overwrite = std::array::from_fn(save_data_exists);
+                sctx.start_play_time();
                 sctx.fade.out_to_black(30).await;
                 sctx.pop_mode(); // Title
                 break;
             }
             TitleEvent::NewGame(true) => {
-                sctx.confirm_save_overwrite = save_data_exists();
+                sctx.confirm_save_overwrite = std::array::from_fn(save_data_exists);
+                sctx.start_play_time();
                 sctx.fade.out_to_black(30).await;
                 sctx.pop_mode(); // Title
 
@@ -531,19 +547,21 @@ pub async fn script_main(mut sctx: ScriptContext) {
                 break;
             }
             TitleEvent::Continue => {
-                match Progress::load() {
-                    Ok(progress) => {
-                        sctx.progress = progress;
-                        sctx.confirm_save_overwrite = false;
-                        sctx.fade.out_to_black(30).await;
-                        sctx.pop_mode(); // Title
-                        break;
-                    }
-                    Err(e) => {
-                        sctx.push_text_box_mode(&format!("Load error:\n{e}"));
-                        let TextBoxEvent::Done = sctx.update_text_box_mode().await;
-                        sctx.pop_mode(); // TextBox
-                    }
+                sctx.push_slot_picker_mode("Load which slot?", false);
+                let event = sctx.update_slot_picker_mode().await;
+                sctx.pop_mode(); // SlotPicker
+                let SlotPickerEvent::Slot(slot) = event else {
+                    continue;
+                };
+                if let Some(progress) = load_progress(&mut sctx, slot).await {
+                    sctx.progress = progress;
+                    sctx.confirm_save_overwrite = std::array::from_fn(save_data_exists);
+                    sctx.confirm_save_overwrite[slot] = false;
+                    sctx.start_play_time();
+                    sctx.fade.out_to_black(30).await;
+                    sctx.pop_mode(); // Title
+                    warp_to_saved_location(&mut sctx).await;
+                    break;
                 }
             }
             TitleEvent::Options => {
@@ -611,7 +629,7 @@ pub async fn script_main(mut sctx: ScriptContext) {
                         sctx.pop_mode();
                     }
                     DebugMenuEvent::Battle(battle) => {
-                        let (enemy, boss_fight) = if battle < 6 {
+                        let (enemies, boss_fight) = if battle < 6 {
                             let group = match battle {
                                 0 => EncounterGroup::Wilderness1,
                                 1 => EncounterGroup::Wilderness2,
@@ -621,22 +639,26 @@ pub async fn script_main(mut sctx: ScriptContext) {
                                 5 => EncounterGroup::FireCastle,
                                 _ => unreachable!(),
                             };
-                            (group.random_enemy(&mut sctx.rng), false)
+                            (
+                                sctx.res.enemies.random_formation(group, &mut sctx.rng),
+                                false,
+                            )
                         } else {
                             (
-                                match battle {
-                                    6 => Enemy::earth_spirit(),
-                                    7 => Enemy::water_spirit(),
-                                    8 => Enemy::fire_spirit(),
+                                vec![match battle {
+                                    6 => sctx.res.enemies.by_name("Earth"),
+                                    7 => sctx.res.enemies.by_name("Water"),
+                                    8 => sctx.res.enemies.by_name("Fire"),
                                     b => panic!("invalid battle number: {b}"),
-                                },
+                                }],
                                 true,
                             )
                         };
-                        sctx.push_battle_mode(enemy, boss_fight);
+                        sctx.push_battle_mode(enemies, boss_fight);
                         handle_battle(&mut sctx).await;
                         sctx.audio.play_music(sctx.level.music).await;
                     }
+                    DebugMenuEvent::ReplayBattle => replay_battle(&mut sctx).await,
                     DebugMenuEvent::SetWeapon(weapon) => {
                         sctx.progress.attack += weapon.as_ref().map(|w| w.attack).unwrap_or(0)
                             - sctx.progress.weapon.as_ref().map(|w| w.attack).unwrap_or(0);
@@ -687,26 +709,6 @@ pub async fn script_main(mut sctx: ScriptContext) {
                         let TextBoxEvent::Done = sctx.update_text_box_mode().await;
                         sctx.pop_mode();
                     }
-                    DebugMenuEvent::ChangeFlag(flag) => {
-                        let (name, new_value) = match flag {
-                            0 => {
-                                sctx.progress.earth_defeated ^= true;
-                                ("earth_defeated", sctx.progress.earth_defeated)
-                            }
-                            1 => {
-                                sctx.progress.water_defeated ^= true;
-                                ("water_defeated", sctx.progress.water_defeated)
-                            }
-                            2 => {
-                                sctx.progress.fire_defeated ^= true;
-                                ("fire_defeated", sctx.progress.fire_defeated)
-                            }
-                            f => panic!("unknown flag number: {f}"),
-                        };
-                        sctx.push_text_box_mode(&format!("{name} is now {new_value}."));
-                        let TextBoxEvent::Done = sctx.update_text_box_mode().await;
-                        sctx.pop_mode();
-                    }
                     DebugMenuEvent::Warp { level_id, x, y } => {
                         warp_to_level(&mut sctx, level_id, x, y).await;
                     }
@@ -714,8 +716,12 @@ pub async fn script_main(mut sctx: ScriptContext) {
                 }
             }
             WalkAroundEvent::Encounter => {
-                if let Some(enemy) = sctx.level.encounters.map(|g| g.random_enemy(&mut sctx.rng)) {
-                    sctx.push_battle_mode(enemy, false);
+                if let Some(enemies) = sctx
+                    .level
+                    .encounters
+                    .map(|g| sctx.res.enemies.random_formation(g, &mut sctx.rng))
+                {
+                    sctx.push_battle_mode(enemies, false);
                     handle_battle(&mut sctx).await;
                     sctx.audio.play_music(sctx.level.music).await;
                 }
@@ -723,15 +729,21 @@ pub async fn script_main(mut sctx: ScriptContext) {
             }
             WalkAroundEvent::MainMenu => {
                 sctx.audio.play_sfx(Sfx::Confirm);
+                sctx.update_play_time();
                 sctx.push_main_menu_mode(false);
                 loop {
                     match sctx.update_main_menu_mode().await {
+                        MainMenuEvent::Bestiary => {
+                            sctx.push_bestiary_mode();
+                            let BestiaryEvent::Done = sctx.update_bestiary_mode().await;
+                            sctx.pop_mode(); // Bestiary
+                        }
                         MainMenuEvent::Done => {
                             sctx.pop_mode(); // MainMenu
                             break;
                         }
                         MainMenuEvent::Options => {
-                            sctx.push_options_mode(52, 78, false);
+                            sctx.push_options_mode(52, 74, false);
                             if !handle_options(&mut sctx).await {
                                 return;
                             }
@@ -752,12 +764,13 @@ pub async fn script_main(mut sctx: ScriptContext) {
                     (talk_script)(&mut sctx).await;
 
                     // Trigger the ending when defeating the final boss.
-                    if sctx.progress.fire_defeated {
+                    if sctx.progress.flag(FIRE_DEFEATED) {
                         sctx.fade.to_color(180, [1.0, 1.0, 1.0, 1.0]).await;
                         sctx.fade.to_color(180, [0.0, 0.0, 0.0, 1.0]).await;
                         sctx.fade.set([0.0; 4]);
 
                         sctx.pop_mode(); // WalkAround
+                        sctx.update_play_time();
                         sctx.push_ending_mode();
                         loop {
                             match sctx.update_ending_mode().await {
@@ -779,13 +792,8 @@ pub async fn script_main(mut sctx: ScriptContext) {
                         sctx.audio.play_music(sctx.level.music).await;
                     }
                 } else if sctx.actors[actor].identifier == ActorType::Chest {
-                    if !sctx
-                        .progress
-                        .collected_chests
-                        .iter()
-                        .map(String::as_str)
-                        .any(|s| s == sctx.level.identifier.as_str())
-                    {
+                    let iid = sctx.actors[actor].iid.clone().expect("chest iid");
+                    if !sctx.progress.collected_chests.contains(&iid) {
                         let chest_opened =
                             match sctx.actors[actor].chest_type.expect("ChestType for Chest") {
                                 ChestType::FireEdge => {
@@ -832,13 +840,11 @@ pub async fn script_main(mut sctx: ScriptContext) {
                             };
 
                         if chest_opened {
-                            sctx.progress
-                                .collected_chests
-                                .push(sctx.level.identifier.clone());
+                            sctx.progress.collected_chests.push(iid);
                         }
                     }
                 } else if sctx.actors[actor].identifier == ActorType::Lever {
-                    if sctx.lever_is_turned() {
+                    if sctx.lever_is_turned(actor) {
                         sctx.push_text_box_mode("Coric turns the lever to the left.");
                     } else {
                         sctx.push_text_box_mode("Coric turns the lever to the right.");
@@ -846,7 +852,7 @@ pub async fn script_main(mut sctx: ScriptContext) {
                     let TextBoxEvent::Done = sctx.update_text_box_mode().await;
                     sctx.pop_mode();
 
-                    sctx.toggle_lever();
+                    sctx.toggle_lever(actor);
                 } else {
                     panic!(
                         "missing on_talk script for {:?} in level {}",
@@ -927,7 +933,70 @@ async fn run_level_on_enter(sctx: &mut ScriptContext) {
     sctx.audio.play_music(sctx.level.music).await;
 }
 
-async fn warp_to_level(sctx: &mut ScriptContext, level_id: &'static str, x: i32, y: i32) {
+async fn load_progress(sctx: &mut ScriptContext, slot: usize) -> Option<Progress> {
+    fn repaired(mut progress: Progress) -> Result<(Progress, Vec<String>), StorageError> {
+        let repairs = progress.repair()?;
+        Ok((progress, repairs))
+    }
+
+    let mut result = Progress::load(slot).and_then(repaired);
+
+    if let Err(e) = &result {
+        if e.is_corruption() && backup_exists(slot) {
+            sctx.push_text_box_mode(&format!("Save data is damaged:\n{e}"));
+            let TextBoxEvent::Done = sctx.update_text_box_mode().await;
+            sctx.pop_mode(); // TextBox
+
+            sctx.push_yes_no_prompt_mode("Restore the newest backup?", "Restore", "Cancel", true);
+            let restore = matches!(
+                sctx.update_yes_no_prompt_mode().await,
+                YesNoPromptEvent::Yes
+            );
+            sctx.pop_mode(); // YesNoPrompt
+            if !restore {
+                return None;
+            }
+            result = Progress::restore_backup(slot).and_then(repaired);
+        }
+    }
+
+    match result {
+        Ok((progress, repairs)) => {
+            if !repairs.is_empty() {
+                let mut lines = vec![format!(
+                    "Save data had {} problem{}:",
+                    repairs.len(),
+                    if repairs.len() == 1 { "" } else { "s" },
+                )];
+                lines.extend(repairs);
+                // Text boxes only fit three lines, so long lists of repairs take a few.
+                for page in lines.chunks(3) {
+                    sctx.push_text_box_mode(&page.join("\n"));
+                    let TextBoxEvent::Done = sctx.update_text_box_mode().await;
+                    sctx.pop_mode(); // TextBox
+                }
+            }
+            Some(progress)
+        }
+        Err(StorageError::UnknownVersion(version)) => {
+            sctx.push_text_box_mode(&format!(
+                "Load error:\nunknown save version {version}\n\
+                 (made by a newer game version?)"
+            ));
+            let TextBoxEvent::Done = sctx.update_text_box_mode().await;
+            sctx.pop_mode(); // TextBox
+            None
+        }
+        Err(e) => {
+            sctx.push_text_box_mode(&format!("Load error:\n{e}"));
+            let TextBoxEvent::Done = sctx.update_text_box_mode().await;
+            sctx.pop_mode(); // TextBox
+            None
+        }
+    }
+}
+
+async fn warp_to_level(sctx: &mut ScriptContext, level_id: &str, x: i32, y: i32) {
     let (level, mut actors) = sctx.level_by_identifier(level_id);
     sctx.actors.truncate(1);
     let mut player = sctx.actors.pop().expect("player actor");
@@ -941,17 +1010,41 @@ async fn warp_to_level(sctx: &mut ScriptContext, level_id: &'static str, x: i32,
     run_level_on_enter(sctx).await;
 }
 
+async fn warp_to_saved_location(sctx: &mut ScriptContext) {
+    let location = &sctx.progress.location;
+    let (level_id, x, y, dir) = if sctx.res.levels.contains_identifier(&location.level_id) {
+        (
+            location.level_id.clone(),
+            location.x,
+            location.y,
+            location.dir,
+        )
+    } else {
+        (String::from("Start"), 6, 3, Direction::South)
+    };
+
+    warp_to_level(sctx, &level_id, x, y).await;
+    sctx.actors[0].face_dir = dir;
+    sctx.actors[0].start_face_animation(dir);
+}
+
 async fn handle_battle(sctx: &mut ScriptContext) -> bool {
-    sctx.actors[0].visible = false;
-    let event = sctx.update_battle_mode().await;
-    sctx.pop_mode();
-    sctx.actors[0].visible = true;
-    sctx.audio.play_music(None).await;
-    sctx.audio.set_music_volume_scripted(100);
+    let (event, recorder) = run_battle(sctx).await;
+    // Recording is only for bug reports, so failing to save it shouldn't get in the way.
+    let _ = recorder.save();
+
+    sctx.progress.stats.battles_fought += 1;
     match event {
-        BattleEvent::Victory => true,
-        BattleEvent::RanAway => false,
+        BattleEvent::Victory => {
+            sctx.progress.stats.battles_won += 1;
+            true
+        }
+        BattleEvent::RanAway => {
+            sctx.progress.stats.battles_fled += 1;
+            false
+        }
         BattleEvent::Defeat => {
+            sctx.progress.stats.battles_lost += 1;
             sctx.fade.out_to_black(90).await;
 
             // warp player back to town
@@ -959,6 +1052,7 @@ async fn handle_battle(sctx: &mut ScriptContext) -> bool {
 
             sctx.progress.hp = sctx.progress.max_hp;
             sctx.progress.mp = sctx.progress.max_mp;
+            sctx.progress.stats.deaths += 1;
             sctx.fade.in_from_black(90).await;
 
             sctx.push_text_box_mode("Coric:\nOuch!");
@@ -970,6 +1064,72 @@ async fn handle_battle(sctx: &mut ScriptContext) -> bool {
     }
 }
 
+// Runs the battle mode that was just pushed until it's over, recording it (or checking it against
+// the recording when replaying) along the way.
+async fn run_battle(sctx: &mut ScriptContext) -> (BattleEvent, SharedMut<BattleRecorder>) {
+    let mut recorder = sctx.battle_recorder.take().expect("battle recorder");
+    // SAFETY: `record_battle` polls the battle that these are shared with.
+    let (battle_recorder, input) =
+        unsafe { (SharedMut::clone(&recorder), SharedMut::clone(&sctx.input)) };
+
+    sctx.actors[0].visible = false;
+    let event = record_battle(battle_recorder, input, sctx.update_battle_mode()).await;
+    sctx.pop_mode();
+    sctx.actors[0].visible = true;
+    sctx.audio.play_music(None).await;
+    sctx.audio.set_music_volume_scripted(100);
+
+    recorder.finish(event.name());
+    (event, recorder)
+}
+
+// Plays the last recorded battle again from its own starting state, leaving the game as it was.
+async fn replay_battle(sctx: &mut ScriptContext) {
+    let loaded = BattleReplay::load().and_then(|replay| {
+        let progress = Progress::from_save_data(&replay.progress).map_err(|e| e.to_string())?;
+        let enemies = replay
+            .enemies
+            .iter()
+            .map(|name| {
+                sctx.res
+                    .enemies
+                    .all()
+                    .iter()
+                    .find(|e| &e.name == name)
+                    .cloned()
+                    .ok_or_else(|| format!("unknown enemy: {name}"))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok((replay, progress, enemies))
+    });
+
+    let msg = match loaded {
+        Ok((replay, progress, enemies)) => {
+            let real_progress = std::mem::replace(&mut sctx.progress, progress);
+            let real_rng = std::mem::replace(&mut sctx.rng, replay.rng());
+            let real_battle_speed = std::mem::replace(&mut sctx.battle_speed, replay.battle_speed);
+            sctx.push_battle_replay_mode(enemies, replay);
+            let (_, recorder) = run_battle(sctx).await;
+            sctx.progress = real_progress;
+            sctx.rng = real_rng;
+            sctx.battle_speed = real_battle_speed;
+
+            match recorder.divergence() {
+                Some(place) => {
+                    format!("The replay diverged {place}.\nSee the console for details.")
+                }
+                None => String::from("The replay matched the recording."),
+            }
+        }
+        Err(e) => format!("Couldn't load {REPLAY_PATH}:\n{e}"),
+    };
+
+    sctx.audio.play_music(sctx.level.music).await;
+    sctx.push_text_box_mode(&msg);
+    let TextBoxEvent::Done = sctx.update_text_box_mode().await;
+    sctx.pop_mode();
+}
+
 async fn handle_options(sctx: &mut ScriptContext) -> bool {
     loop {
         match sctx.update_options_mode().await {
@@ -34,10 +34,12 @@ const FRAGMENT: &str = r#"#version 100
 precision mediump float;
 
 varying vec2 tex_coord;
+uniform vec4 tint;
 uniform sampler2D tex;
 
 void main() {
-    gl_FragColor = texture2D(tex, tex_coord);
+    vec4 color = texture2D(tex, tex_coord);
+    gl_FragColor = vec4(mix(color.rgb, tint.rgb, tint.a), color.a);
 }
 "#;
 
@@ -47,6 +49,8 @@ pub struct Uniforms {
     pub px_dest_offset: [f32; 2],
     pub px_framebuffer_size: [f32; 2],
     pub px_texture_size: [f32; 2],
+    // Mixed into the texture color by its alpha, for flashes.
+    pub tint: [f32; 4],
 }
 
 pub fn pipeline(gctx: &mut GlContext) -> Pipeline {
@@ -64,6 +68,7 @@ pub fn pipeline(gctx: &mut GlContext) -> Pipeline {
                         UniformDesc::new("px_dest_offset", UniformType::Float2),
                         UniformDesc::new("px_framebuffer_size", UniformType::Float2),
                         UniformDesc::new("px_texture_size", UniformType::Float2),
+                        UniformDesc::new("tint", UniformType::Float4),
                     ],
                 },
             },
@@ -9,9 +9,10 @@ precision mediump float;
 attribute vec2 pos;
 varying vec2 tex_coord;
 uniform vec2 scale;
+uniform vec2 offset;
 
 void main() {
-    gl_Position = vec4((pos * 2.0 - 1.0) * scale, 0.0, 1.0);
+    gl_Position = vec4((pos * 2.0 - 1.0 + offset) * scale, 0.0, 1.0);
     tex_coord = pos;
 }
 "#;
@@ -37,6 +38,7 @@ void main() {
 #[repr(C)]
 pub struct Uniforms {
     pub scale: [f32; 2],
+    pub offset: [f32; 2],
     pub fade: [f32; 4],
 }
 
@@ -52,6 +54,7 @@ pub fn pipeline(gctx: &mut GlContext) -> Pipeline {
                 uniforms: UniformBlockLayout {
                     uniforms: vec![
                         UniformDesc::new("scale", UniformType::Float2),
+                        UniformDesc::new("offset", UniformType::Float2),
                         UniformDesc::new("fade", UniformType::Float4),
                     ],
                 },
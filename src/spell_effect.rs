@@ -0,0 +1,71 @@
+use crate::progress::*;
+use crate::resources::*;
+use crate::sprite::*;
+
+use miniquad::GlContext;
+
+// All spell sprite sheets have frames of this size.
+const SPELL_EFFECT_SIZE: i32 = 32;
+
+// A spell's animation, played through once over whoever it was cast on.
+pub struct SpellEffect {
+    sprite: Sprite,
+    // Top-left corner of the sprite.
+    x: i32,
+    y: i32,
+    frames_left: u32,
+}
+
+impl SpellEffect {
+    // Centered on the given point.
+    pub fn new(
+        gctx: &mut GlContext,
+        res: &Resources,
+        magic: Magic,
+        middle_x: i32,
+        middle_y: i32,
+    ) -> Self {
+        let mut sprite = Sprite::new(gctx, res, sprite_path(magic));
+        sprite.start_animation("cast");
+        let frames_left = sprite.animation_duration() * 60 / 1000;
+
+        Self {
+            sprite,
+            x: middle_x - SPELL_EFFECT_SIZE / 2,
+            y: middle_y - SPELL_EFFECT_SIZE / 2,
+            frames_left,
+        }
+    }
+
+    pub fn animate(&mut self) {
+        self.sprite.animate();
+        self.frames_left = self.frames_left.saturating_sub(1);
+    }
+
+    pub fn draw(&self, gctx: &mut GlContext) {
+        self.sprite.draw(gctx, self.x, self.y);
+    }
+
+    pub fn finished(&self) -> bool {
+        self.frames_left == 0
+    }
+}
+
+// Color flashed over an enemy hit by the spell, in the same colors as the spell's animation.
+pub fn spell_flash_tint(magic: Magic) -> [f32; 4] {
+    match magic {
+        Magic::Heal => [0.6, 0.9, 0.4, 0.75],
+        Magic::EarthEdge => [0.8, 0.6, 0.0, 0.75],
+        Magic::WaterEdge => [0.0, 0.6, 1.0, 0.75],
+        Magic::FireEdge => [1.0, 0.4, 0.0, 0.75],
+    }
+}
+
+fn sprite_path(magic: Magic) -> &'static str {
+    match magic {
+        Magic::Heal => "spell-heal.png",
+        Magic::EarthEdge => "spell-earth-edge.png",
+        Magic::WaterEdge => "spell-water-edge.png",
+        Magic::FireEdge => "spell-fire-edge.png",
+    }
+}
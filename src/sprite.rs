@@ -89,7 +89,20 @@ impl Sprite {
         }
     }
 
+    // How long the current animation takes to play through once, in milliseconds.
+    pub fn animation_duration(&self) -> u32 {
+        let tag_data = &self.json.meta.frame_tags[self.tag];
+        self.json.frames[tag_data.from..=tag_data.to]
+            .iter()
+            .map(|f| f.duration)
+            .sum()
+    }
+
     pub fn draw(&self, gctx: &mut GlContext, x: i32, y: i32) {
+        self.draw_tinted(gctx, x, y, [0.0; 4]);
+    }
+
+    pub fn draw_tinted(&self, gctx: &mut GlContext, x: i32, y: i32, tint: [f32; 4]) {
         let aseprite::Rect {
             x: src_x, y: src_y, ..
         } = self.json.frames[self.frame].frame;
@@ -101,6 +114,7 @@ impl Sprite {
             px_dest_offset: [x as f32, y as f32],
             px_framebuffer_size: [SCREEN_WIDTH as f32, SCREEN_HEIGHT as f32],
             px_texture_size: self.px_texture_size,
+            tint,
         }));
         gctx.draw(0, 6, 1);
     }
@@ -123,6 +137,15 @@ impl Sprite {
         }
     }
 
+    pub fn start_face_animation(&mut self, dir: Direction) {
+        self.start_animation(match dir {
+            Direction::North => "face_n",
+            Direction::East => "face_e",
+            Direction::South => "face_s",
+            Direction::West => "face_w",
+        });
+    }
+
     pub fn start_walk_animation(&mut self, dir: Direction) {
         self.start_animation(match dir {
             Direction::North => "walk_n",
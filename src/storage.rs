@@ -0,0 +1,91 @@
+use miniserde::de::Visitor;
+use miniserde::json::{Number, Value};
+use miniserde::Deserialize;
+
+#[derive(Debug)]
+pub enum StorageError {
+    Unavailable,
+    Missing,
+    BadHeader,
+    BadChecksum,
+    UnknownVersion(u32),
+    Syntax,
+    Parse(String),
+    InvalidValue(String),
+}
+
+impl StorageError {
+    pub fn is_corruption(&self) -> bool {
+        matches!(
+            self,
+            Self::BadHeader
+                | Self::BadChecksum
+                | Self::Syntax
+                | Self::Parse(_)
+                | Self::InvalidValue(_)
+        )
+    }
+}
+
+impl std::fmt::Display for StorageError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> Result<(), std::fmt::Error> {
+        match self {
+            Self::Unavailable => f.write_str("storage unavailable"),
+            Self::Missing => f.write_str("no data found"),
+            Self::BadHeader => f.write_str("invalid header"),
+            Self::BadChecksum => f.write_str("checksum mismatch"),
+            Self::UnknownVersion(version) => write!(f, "unknown version {version}"),
+            Self::Syntax => f.write_str("data is not valid JSON"),
+            Self::Parse(location) if location.is_empty() => f.write_str("failed to parse data"),
+            Self::Parse(location) => write!(f, "failed to parse {location}"),
+            Self::InvalidValue(what) => write!(f, "invalid value: {what}"),
+        }
+    }
+}
+
+// Like `miniserde::json::from_str`, but reports where parsing failed.
+//
+// miniserde errors carry no information, so the data is first parsed into a `Value` tree, which is
+// then fed to the deserializer by hand while tracking the path to the value being visited.
+pub fn from_json<T: Deserialize>(data: &str) -> Result<T, StorageError> {
+    let value: Value = miniserde::json::from_str(data).map_err(|_| StorageError::Syntax)?;
+    let mut out = None;
+    let mut location = String::new();
+    visit(&value, T::begin(&mut out), &mut location).map_err(|_| StorageError::Parse(location))?;
+    out.ok_or_else(|| StorageError::Parse(String::new()))
+}
+
+// On failure, `location` is left holding the path to the value that failed to parse.
+fn visit(value: &Value, visitor: &mut dyn Visitor, location: &mut String) -> miniserde::Result<()> {
+    match value {
+        Value::Null => visitor.null(),
+        Value::Bool(b) => visitor.boolean(*b),
+        Value::Number(Number::U64(n)) => visitor.nonnegative(*n),
+        Value::Number(Number::I64(n)) => visitor.negative(*n),
+        Value::Number(Number::F64(n)) => visitor.float(*n),
+        Value::String(s) => visitor.string(s),
+        Value::Array(array) => {
+            let mut seq = visitor.seq()?;
+            for (i, element) in array.iter().enumerate() {
+                let len = location.len();
+                location.push_str(&format!("[{i}]"));
+                visit(element, seq.element()?, location)?;
+                location.truncate(len);
+            }
+            seq.finish()
+        }
+        Value::Object(object) => {
+            let mut map = visitor.map()?;
+            for (key, field) in object.iter() {
+                let len = location.len();
+                if !location.is_empty() {
+                    location.push('.');
+                }
+                location.push_str(key);
+                visit(field, map.key(key)?, location)?;
+                location.truncate(len);
+            }
+            map.finish()
+        }
+    }
+}
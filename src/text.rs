@@ -58,6 +58,7 @@ impl Text {
                 px_dest_offset: self.offset,
                 px_framebuffer_size: [SCREEN_WIDTH as f32, SCREEN_HEIGHT as f32],
                 px_texture_size: [self.font.width as f32, self.font.height as f32],
+                tint: [0.0; 4],
             }));
             gctx.draw(0, 6, self.shown_chars);
         }
@@ -72,10 +73,17 @@ impl Text {
     }
 
     pub async fn reveal(&mut self) {
+        self.reveal_at(1).await;
+    }
+
+    pub async fn reveal_at(&mut self, chars_per_frame: i32) {
         self.hide_all_chars();
         while !self.all_chars_shown() {
             wait_once().await;
-            self.show_one_char();
+            self.shown_chars = self
+                .shown_chars
+                .saturating_add(chars_per_frame)
+                .min(self.local_buf.len() as i32);
         }
     }
 
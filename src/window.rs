@@ -240,6 +240,7 @@ impl<const N: usize> WindowPart<N> {
             px_dest_offset: [x, y],
             px_framebuffer_size: [SCREEN_WIDTH as f32, SCREEN_HEIGHT as f32],
             px_texture_size: self.px_texture_size,
+            tint: [0.0; 4],
         }));
         gctx.draw(0, 6, self.len as i32);
     }